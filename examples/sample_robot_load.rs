@@ -11,7 +11,7 @@ use bevy_rapier3d::{
 };
 use bevy_serialization_urdf::{
     loaders::urdf_loader::Urdf,
-    plugin::{AssetSourcesUrdfPlugin, UrdfSerializationPlugin}, resources::CachedUrdf,
+    plugin::{AssetSourcesUrdfPlugin, UrdfSerializationPlugin}, resources::{CachedUrdf, UrdfSpawnSettings},
 };
 use bevy_ui_extras::{visualize_components_for, UiExtrasDebug};
 use egui::{text::LayoutJob, Color32, Frame, Margin, Rounding, ScrollArea, Shadow, Stroke, TextFormat};
@@ -50,7 +50,13 @@ fn main() {
         .add_plugins(SerializationPhysicsPlugin)
         .add_plugins(SerializationBasePlugin)
 
-        .add_plugins(UrdfSerializationPlugin)
+        // the bundled urdfs leave out `<collision>`s, so collide with their visuals instead.
+        .add_plugins(UrdfSerializationPlugin {
+            spawn_settings: UrdfSpawnSettings {
+                colliders_from_visuals: true,
+                ..default()
+            },
+        })
         // rapier physics plugins
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
         .add_plugins(RapierDebugRenderPlugin::default())
@@ -77,26 +83,9 @@ fn main() {
         .add_systems(Update, control_robot)
         // .add_systems(Update, make_robots_selectable)
         .add_systems(Update, bind_left_and_right_wheel)
-        .add_systems(Update, freeze_spawned_robots)
         .run();
 }
 
-#[derive(Component)]
-pub struct WasFrozen;
-
-//FIXME: physics bodies fly out of control when spawned, this freezes them for the user to unpause until thats fixed.
-pub fn freeze_spawned_robots(
-    mut robots: Query<
-        (Entity, &mut RigidBodyFlag),
-        (With<StructureFlag>, Without<JointFlag>, Without<WasFrozen>),
-    >,
-    mut commands: Commands,
-) {
-    for (e, mut body) in robots.iter_mut() {
-        *body = RigidBodyFlag::Fixed;
-        commands.entity(e).insert(WasFrozen);
-    }
-}
 #[derive(Component, Reflect, Display)]
 pub enum Wheel {
     Left,
@@ -228,6 +217,13 @@ pub fn queue_urdf_load_requests(
         position: Transform::from_xyz(0.0, 2.0, 0.0),
         ..Default::default()
     });
+    // the same urdf can be spawned multiple times, each copy at its own pose.
+    urdf_load_requests.requests.push_front(AssetSpawnRequest {
+        source: load_urdf_path.to_owned().into(),
+        position: Transform::from_xyz(2.0, 2.0, 0.0)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
+        ..Default::default()
+    });
 }
 
 /// set up a simple 3D scene
@@ -0,0 +1,241 @@
+//! components for urdf data that doesn't have an equivalent in `bevy_serialization_extras`.
+
+use bevy_ecs::prelude::*;
+use bevy_reflect::Reflect;
+use bevy_transform::prelude::*;
+use glam::{DQuat, Mat3, Quat, Vec3};
+use nalgebra::{Matrix3, SymmetricEigen};
+
+use crate::wrappers::urdf_to_bevy_basis;
+
+/// marks the root link of a spawned urdf.
+///
+/// When links are spawned as a hierarchy (see [`crate::resources::UrdfSpawnSettings`]), every other link of the
+/// robot is a descendant of this entity, so moving or despawning it(recursively) affects the whole robot.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct RootLink;
+
+/// marks one of a link's `<visual>` elements. Spawned as a child of its link.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct LinkVisual;
+
+/// marks one of a link's `<collision>` elements. Spawned as a hidden child of its link.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct LinkCollision;
+
+/// the name of the urdf joint onto a link. Lives on the joint's entity(its child link), so the joint is saved back
+/// out under the name ROS controllers and transmissions look it up by.
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct JointName {
+    pub name: String,
+}
+
+/// the name of a `<visual>`'s urdf `<material>`.
+///
+/// Visuals are always spawned with a [`MaterialFlag`] so they render, but only the ones with a `MaterialName` are
+/// saved back out with a `<material>`.
+///
+/// [`MaterialFlag`]: bevy_serialization_extras::prelude::material::MaterialFlag
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct MaterialName {
+    pub name: String,
+}
+
+/// the parts of a link's `<inertial>` element that don't fit in a [`MassFlag`](bevy_serialization_extras::prelude::mass::MassFlag).
+///
+/// Handed to rapier together with the link's [`MassFlag`](bevy_serialization_extras::prelude::mass::MassFlag) by
+/// [`apply_inertials`](crate::systems::apply_inertials), in place of what rapier would work out from the link's
+/// colliders.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct InertialFlag {
+    /// the center of mass frame, relative to the link.
+    pub center_of_mass: Transform,
+    /// inertia tensor about the center of mass, along the axes of `center_of_mass` as urdf lays them out.
+    pub ixx: f32,
+    pub ixy: f32,
+    pub ixz: f32,
+    pub iyy: f32,
+    pub iyz: f32,
+    pub izz: f32,
+}
+
+impl InertialFlag {
+    /// the full, symmetric, 3x3 inertia tensor.
+    pub fn tensor(&self) -> Mat3 {
+        Mat3::from_cols(
+            Vec3::new(self.ixx, self.ixy, self.ixz),
+            Vec3::new(self.ixy, self.iyy, self.iyz),
+            Vec3::new(self.ixz, self.iyz, self.izz),
+        )
+    }
+
+    /// the principal axes of inertia, as a rotation of the link spawned with `coordinates`, and the principal
+    /// moments of inertia along them.
+    pub fn principal_inertia(&self, coordinates: UrdfCoordinates) -> (Quat, Vec3) {
+        // the tensor is along urdf's axes, which geometry is spawned in, see `UrdfTransform::into_geometry_transform`.
+        let axes =
+            Mat3::from_quat(self.center_of_mass.rotation * coordinates.pose_basis().as_quat());
+        let tensor = axes * self.tensor() * axes.transpose();
+        let eigen = SymmetricEigen::new(Matrix3::from_fn(|row, col| tensor.col(col)[row]));
+        let mut principal_axes = Mat3::from_cols_slice(eigen.eigenvectors.as_slice());
+        // eigenvectors may come out as a reflection rather than a rotation.
+        if principal_axes.determinant() < 0.0 {
+            principal_axes.z_axis = -principal_axes.z_axis;
+        }
+        (
+            Quat::from_mat3(&principal_axes).normalize(),
+            Vec3::from_slice(eigen.eigenvalues.as_slice()),
+        )
+    }
+}
+
+/// an image file applied as the base color texture of an entity's material, with the material's color as a tint.
+/// Like [`GeometryFile`](bevy_serialization_extras::prelude::mesh::GeometryFile), `source` may be a `package://` path.
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct TextureFile {
+    pub source: String,
+}
+
+/// how urdf's z-up coordinates are brought into bevy's y-up world.
+///
+/// Set through [`UrdfSpawnSettings`](crate::resources::UrdfSpawnSettings), and kept on every spawned entity so
+/// robots are converted back the same way.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum UrdfCoordinates {
+    /// convert every pose, primitive and mesh to y-up.
+    #[default]
+    ConvertToYUp,
+    /// keep everything in urdf's z-up coordinates, and turn the whole robot upright with one rotation on its root link.
+    RootRotation,
+    /// leave coordinates untouched, so the robot lies on its side in a y-up world.
+    Untouched,
+}
+
+impl UrdfCoordinates {
+    /// change of basis applied to every pose in the urdf.
+    pub fn pose_basis(self) -> DQuat {
+        match self {
+            Self::ConvertToYUp => urdf_to_bevy_basis(),
+            Self::RootRotation | Self::Untouched => DQuat::IDENTITY,
+        }
+    }
+
+    /// rotation applied once, to the root link.
+    pub fn root_rotation(self) -> Quat {
+        match self {
+            Self::RootRotation => urdf_to_bevy_basis().as_quat(),
+            Self::ConvertToYUp | Self::Untouched => Quat::IDENTITY,
+        }
+    }
+}
+
+/// a joint that follows another joint, from urdf's `<mimic>`. Lives on the follower's joint entity.
+///
+/// The follower's motor targets are kept at `multiplier * leader + offset` by
+/// [`apply_mimic_joints`](crate::systems::apply_mimic_joints).
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct MimicJoint {
+    /// the leading joint's entity(the child link of the joint named in `<mimic>`).
+    pub leader: Entity,
+    pub multiplier: f64,
+    pub offset: f64,
+}
+
+impl Default for MimicJoint {
+    fn default() -> Self {
+        Self {
+            leader: Entity::PLACEHOLDER,
+            multiplier: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+/// a `floating` joint spawned without a joint, see
+/// [`UrdfSpawnSettings::detach_floating_joints`](crate::resources::UrdfSpawnSettings::detach_floating_joints).
+/// Lives on the joint's child link, so the joint is still saved back out.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct DetachedJoint {
+    /// the joint's parent link.
+    pub parent: Entity,
+    /// the joint's `<origin>`, in the robot's [`UrdfCoordinates`].
+    pub origin: Transform,
+}
+
+impl Default for DetachedJoint {
+    fn default() -> Self {
+        Self {
+            parent: Entity::PLACEHOLDER,
+            origin: Transform::IDENTITY,
+        }
+    }
+}
+
+/// a joint's urdf `<safety_controller>`. Lives on the joint's entity, next to its [`JointFlag`].
+///
+/// Joint motors only have targets to go on, so [`apply_safety_controllers`](crate::systems::apply_safety_controllers)
+/// keeps the target position within the soft limits, and the target velocity within the bounds `k_position`
+/// gives at the joint's current position. `k_velocity` bounds effort against the joint's measured velocity, which
+/// motors don't have, so it's only kept for saving back to urdf.
+///
+/// [`JointFlag`]: bevy_serialization_extras::prelude::link::JointFlag
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct JointSafetyController {
+    pub soft_lower_limit: f64,
+    pub soft_upper_limit: f64,
+    pub k_position: f64,
+    pub k_velocity: f64,
+}
+
+impl JointSafetyController {
+    /// the velocities a joint at `position` may move at: towards the soft limits, slowing down as it nears them,
+    /// and back inside them when past.
+    pub fn velocity_bounds(&self, position: f64) -> (f64, f64) {
+        (
+            -self.k_position * (position - self.soft_lower_limit),
+            -self.k_position * (position - self.soft_upper_limit),
+        )
+    }
+}
+
+/// a link joined to its parent by a fixed joint, folded into another link's rigid body by
+/// [`UrdfSpawnSettings::merge_fixed_joints`](crate::resources::UrdfSpawnSettings::merge_fixed_joints).
+///
+/// The link is still its own entity(a descendant of `body`), so its visuals, colliders and transform stay
+/// addressable, but it has no rigid body or joint of its own.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct MergedLink {
+    /// the link whose rigid body this link is part of.
+    pub body: Entity,
+}
+
+impl Default for MergedLink {
+    fn default() -> Self {
+        Self {
+            body: Entity::PLACEHOLDER,
+        }
+    }
+}
+
+/// a link's own mass and inertia, where its [`MassFlag`] and [`InertialFlag`] don't hold them: merged links have
+/// no mass of their own for physics, and the links they are merged into carry the mass of the whole body.
+///
+/// [`MassFlag`]: bevy_serialization_extras::prelude::mass::MassFlag
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct LinkInertial {
+    pub mass: f32,
+    pub inertial: InertialFlag,
+}
@@ -1,7 +1,10 @@
+pub mod components;
 pub mod loaders;
 pub mod plugin;
 pub mod wrappers;
 pub mod resources;
+pub mod savers;
+pub mod systems;
 // pub mod prelude {
 //     pub use crate:: {
 //         plugins::*,
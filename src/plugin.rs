@@ -1,4 +1,4 @@
-use bevy_asset::{io::{file::FileAssetReader, AssetSource}, AssetApp};
+use bevy_asset::{io::{file::{FileAssetReader, FileAssetWriter}, AssetSource}, AssetApp};
 /// plugin that contains everything required for a urdf -> bevy conversion
 ///
 /// NOTE: !!! .dae is not supported! If a .dae support plugin gets added, make an issue, and it can be added.
@@ -12,9 +12,22 @@ use bevy_asset::{io::{file::FileAssetReader, AssetSource}, AssetApp};
 use bevy_serialization_extras::prelude::SerializeManyAsOneFor;
 
 use bevy_app::prelude::*;
+use bevy_ecs::schedule::{IntoSystemConfigs, IntoSystemSetConfigs, SystemSet};
+use bevy_rapier3d::plugin::PhysicsSet;
 
 use crate::{
-    loaders::urdf_loader::{Urdf, UrdfLoaderPlugin}, resources::CachedUrdf, wrappers::LinkQuery
+    components::{
+        DetachedJoint, InertialFlag, JointName, JointSafetyController, LinkCollision, LinkInertial, LinkVisual,
+        MaterialName, MergedLink, MimicJoint, RootLink, TextureFile, UrdfCoordinates,
+    },
+    loaders::urdf_loader::{Urdf, UrdfLoaderPlugin},
+    resources::{CachedUrdf, JointMotorFallbacks, UrdfSpawnSettings},
+    savers::urdf_saver::SaveUrdfRequest,
+    systems::{
+        apply_inertials, apply_mimic_joints, apply_safety_controllers, apply_texture_files,
+        clamp_motors_to_joint_limits, save_requested_urdfs, sync_joint_motors,
+    },
+    wrappers::LinkQuery,
 };
 
 const PACKAGE: &str = "package";
@@ -30,21 +43,76 @@ impl Plugin for AssetSourcesUrdfPlugin {
         let path = self.assets_folder_local_path.clone();
         app.register_asset_source(
             PACKAGE,
-            AssetSource::build().with_reader(
-                move || Box::new(FileAssetReader::new(path.clone()))
-            ),
+            AssetSource::build()
+                .with_reader({
+                    let path = path.clone();
+                    move || Box::new(FileAssetReader::new(path.clone()))
+                })
+                // so robots can be saved back to where they were loaded from, see `SaveUrdfRequest`.
+                .with_writer(move |create_root| {
+                    Some(Box::new(FileAssetWriter::new(path.clone(), create_root)))
+                }),
         );
     }
 }
 
-pub struct UrdfSerializationPlugin;
+/// the systems that keep [`JointFlag`](bevy_serialization_extras::prelude::link::JointFlag) motor targets in line
+/// with urdf: mimic joints, safety controllers and effort/velocity limits.
+///
+/// Runs in [`PostUpdate`], before rapier's [`PhysicsSet::SyncBackend`], so targets written in [`Update`] are always
+/// constrained before physics steps. The constrained motors are handed to rapier's joints by the set itself, so
+/// it doesn't matter when [`JointFlag`]s are synced to rapier. Systems writing targets later than [`Update`] should
+/// run before this set.
+///
+/// [`JointFlag`]: bevy_serialization_extras::prelude::link::JointFlag
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UrdfJointSystems;
+
+#[derive(Default)]
+pub struct UrdfSerializationPlugin {
+    pub spawn_settings: UrdfSpawnSettings,
+}
 
 impl Plugin for UrdfSerializationPlugin {
     fn build(&self, app: &mut App) {
         app
         .register_type::<CachedUrdf>()
+        .register_type::<UrdfSpawnSettings>()
+        .register_type::<JointMotorFallbacks>()
+        .register_type::<RootLink>()
+        .register_type::<LinkVisual>()
+        .register_type::<LinkCollision>()
+        .register_type::<InertialFlag>()
+        .register_type::<TextureFile>()
+        .register_type::<UrdfCoordinates>()
+        .register_type::<MimicJoint>()
+        .register_type::<JointSafetyController>()
+        .register_type::<MergedLink>()
+        .register_type::<LinkInertial>()
+        .register_type::<JointName>()
+        .register_type::<MaterialName>()
+        .register_type::<DetachedJoint>()
         .add_plugins(UrdfLoaderPlugin)
         .insert_resource(CachedUrdf::default())
-        .add_plugins(SerializeManyAsOneFor::<LinkQuery, Urdf>::default());
+        .insert_resource(self.spawn_settings.clone())
+        .add_plugins(SerializeManyAsOneFor::<LinkQuery, Urdf>::default())
+        .add_event::<SaveUrdfRequest>()
+        .add_systems(Update, apply_texture_files)
+        .add_systems(Update, save_requested_urdfs)
+        .add_systems(
+            PostUpdate,
+            (
+                apply_mimic_joints,
+                // mimicked targets are kept within the follower's own soft limits too.
+                apply_safety_controllers,
+                clamp_motors_to_joint_limits,
+                sync_joint_motors,
+            )
+                .chain()
+                .in_set(UrdfJointSystems),
+        )
+        .configure_sets(PostUpdate, UrdfJointSystems.before(PhysicsSet::SyncBackend))
+        // after extras hands `MassFlag`s to rapier in `Update`, so the urdf's full mass properties win.
+        .add_systems(PostUpdate, apply_inertials.before(PhysicsSet::SyncBackend));
     }
 }
@@ -2,10 +2,57 @@ use bevy_asset::Handle;
 use bevy_ecs::prelude::*;
 use bevy_reflect::Reflect;
 
-use crate::loaders::urdf_loader::Urdf;
+use crate::{components::UrdfCoordinates, loaders::urdf_loader::Urdf};
 
 #[derive(Resource, Default, Reflect)]
 #[reflect(Resource)]
 pub struct CachedUrdf {
     pub urdf: Handle<Urdf>,
-}
\ No newline at end of file
+}
+
+/// settings for how [`Urdf`]s are turned into entities.
+#[derive(Resource, Default, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct UrdfSpawnSettings {
+    /// spawn links as bevy children of their parent link, with their joint's origin as their local transform.
+    /// Otherwise, every link is a top level entity placed at its world pose.
+    pub link_hierarchy: bool,
+    pub coordinates: UrdfCoordinates,
+    /// spawn the child link of a `floating` joint without any joint, rather than with one that leaves every axis
+    /// free. Detached links keep their joint in a [`DetachedJoint`](crate::components::DetachedJoint), so they are
+    /// still read back out as `floating` joints.
+    pub detach_floating_joints: bool,
+    pub motor_fallbacks: JointMotorFallbacks,
+    /// fold links joined by fixed joints into a single rigid body, with their colliders as one compound collider
+    /// and their masses combined, instead of simulating fixed joints. See [`MergedLink`](crate::components::MergedLink).
+    ///
+    /// The body's combined mass, center of mass and inertia are kept in its `MassFlag` and
+    /// [`InertialFlag`](crate::components::InertialFlag), and handed to rapier like any other link's, see
+    /// [`apply_inertials`](crate::systems::apply_inertials).
+    pub merge_fixed_joints: bool,
+    /// give links without any `<collision>` colliders built from their `<visual>` geometry instead, for urdfs that
+    /// leave collisions out. Render meshes are often far more detailed than collision geometry needs to be, so
+    /// prefer `<collision>` elements where the urdf can be edited. These colliders aren't saved back out.
+    pub colliders_from_visuals: bool,
+}
+
+/// joint motor settings for urdfs that leave them out, see [`joint_motor`](crate::wrappers::joint_motor).
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct JointMotorFallbacks {
+    /// used without `<dynamics damping>`. Defaults to rapier's joint damping.
+    pub damping: f32,
+    /// used without `<limit effort>` or `<dynamics friction>`. Defaults to unlimited.
+    ///
+    /// Joints with friction but no effort limit get their friction as max force instead, so they hold back like
+    /// unactuated joints rather than being driven. Give joints you want to drive an effort limit.
+    pub max_force: f32,
+}
+
+impl Default for JointMotorFallbacks {
+    fn default() -> Self {
+        Self {
+            damping: 20.0,
+            max_force: f32::MAX,
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod urdf_saver;
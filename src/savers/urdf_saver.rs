@@ -0,0 +1,93 @@
+//! saver for writing [`Urdf`]s back out as urdf xml, for use with ROS tooling.
+
+use std::path::Path;
+
+use bevy_asset::{
+    io::{AssetWriterError, MissingAssetSourceError, MissingAssetWriterError, Writer},
+    saver::{AssetSaver, SavedAsset},
+    AssetPath, AssetServer, AsyncWriteExt, ErasedLoadedAsset, LoadedAsset,
+};
+use bevy_ecs::prelude::*;
+use bevy_utils::BoxedFuture;
+use thiserror::Error;
+
+use crate::loaders::urdf_loader::{Urdf, UrdfLoader};
+
+#[derive(Default)]
+pub struct UrdfSaver;
+
+/// Possible errors that can be produced by [`UrdfSaver`]
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum UrdfSaverError {
+    #[error("Failed to write Urdf")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize urdf")]
+    SerializingError(#[from] urdf_rs::UrdfError),
+    #[error(transparent)]
+    MissingSource(#[from] MissingAssetSourceError),
+    #[error(transparent)]
+    MissingWriter(#[from] MissingAssetWriterError),
+    #[error(transparent)]
+    Writer(#[from] AssetWriterError),
+}
+
+impl Urdf {
+    /// serializes the robot to urdf xml.
+    pub fn to_xml_string(&self) -> Result<String, UrdfSaverError> {
+        Ok(urdf_rs::write_to_string(&self.robot)?)
+    }
+
+    /// writes the robot as urdf xml to the file at `path`. This blocks until the file is written, see
+    /// [`save_urdf`] for writing without blocking.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), UrdfSaverError> {
+        std::fs::write(path, self.to_xml_string()?)?;
+        Ok(())
+    }
+}
+
+#[allow(refining_impl_trait)]
+impl AssetSaver for UrdfSaver {
+    type Asset = Urdf;
+    type Settings = ();
+    type OutputLoader = UrdfLoader;
+    type Error = UrdfSaverError;
+    fn save<'a>(
+        &'a self,
+        writer: &'a mut Writer,
+        asset: SavedAsset<'a, Self::Asset>,
+        _settings: &'a (),
+    ) -> BoxedFuture<'a, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let xml = asset.to_xml_string()?;
+            writer.write_all(xml.as_bytes()).await?;
+            writer.flush().await?;
+            Ok(())
+        })
+    }
+}
+
+/// writes `urdf` with [`UrdfSaver`] to `path`, through the writer of `path`'s asset source.
+pub async fn save_urdf(
+    asset_server: &AssetServer,
+    urdf: Urdf,
+    path: &AssetPath<'_>,
+) -> Result<(), UrdfSaverError> {
+    let source = asset_server.get_source(path.source().clone())?;
+    let mut writer = source.writer()?.write(path.path()).await?;
+    let loaded = ErasedLoadedAsset::from(LoadedAsset::from(urdf));
+    let asset = SavedAsset::<Urdf>::from_loaded(&loaded)
+        .expect("a loaded urdf should always be saveable as a urdf");
+    UrdfSaver.save(&mut *writer, asset, &()).await
+}
+
+/// request to write the spawned robot with the given [`StructureFlag`](bevy_serialization_extras::prelude::link::StructureFlag)
+/// name to `path`, e.g. `robots/diff_bot.xml` or `package://urdf/diff_bot.xml`. The file is written in the
+/// background, through the asset source's writer.
+///
+/// Each spawn of a urdf has a structure name of its own, see [`SpawnUrdf`](crate::wrappers::SpawnUrdf).
+#[derive(Event, Clone, Debug)]
+pub struct SaveUrdfRequest {
+    pub structure: String,
+    pub path: AssetPath<'static>,
+}
@@ -0,0 +1,292 @@
+//! systems that keep urdf specific components in sync with the rest of the world.
+
+use bevy_asset::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::Children;
+use bevy_log::warn;
+use bevy_pbr::StandardMaterial;
+use bevy_rapier3d::prelude::{
+    AdditionalMassProperties, ColliderMassProperties, ImpulseJoint, JointAxis, MassProperties,
+};
+use bevy_render::texture::Image;
+use bevy_serialization_extras::prelude::{
+    colliders::ColliderFlag, link::JointFlag, mass::MassFlag, rigidbodies::RigidBodyFlag,
+    IntoHashMap,
+};
+use bevy_tasks::IoTaskPool;
+use bevy_transform::prelude::GlobalTransform;
+
+use crate::{
+    components::{InertialFlag, JointSafetyController, MimicJoint, TextureFile, UrdfCoordinates},
+    loaders::urdf_loader::Urdf,
+    savers::urdf_saver::{save_urdf, SaveUrdfRequest},
+    wrappers::{driven_motor, joint_position, LinkQuery},
+};
+
+/// applies each [`TextureFile`] as the base color texture of its entity's material.
+///
+/// The textured material is a copy, so materials shared with untextured entities are left alone.
+pub fn apply_texture_files(
+    textured: Query<
+        (Entity, &TextureFile, &Handle<StandardMaterial>),
+        Or<(Changed<TextureFile>, Changed<Handle<StandardMaterial>>)>,
+    >,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (e, texture, material_handle) in textured.iter() {
+        let Some(material) = materials.get(material_handle) else {
+            continue;
+        };
+        let texture_handle: Handle<Image> = asset_server.load(texture.source.clone());
+        if material.base_color_texture.as_ref() == Some(&texture_handle) {
+            continue;
+        }
+        let textured_material = StandardMaterial {
+            base_color_texture: Some(texture_handle),
+            ..material.clone()
+        };
+        commands.entity(e).insert(materials.add(textured_material));
+    }
+}
+
+/// hands each link's urdf mass, center of mass and inertia to rapier, in place of what rapier would work out from
+/// its colliders. The colliders are left massless, so the body ends up with the urdf's values exactly.
+///
+/// Links without a mass(urdfs leave `<inertial>` out of links that are only frames) are left to their colliders.
+#[allow(clippy::type_complexity)]
+pub fn apply_inertials(
+    links: Query<
+        (
+            Entity,
+            &MassFlag,
+            &InertialFlag,
+            Option<&UrdfCoordinates>,
+            Option<&AdditionalMassProperties>,
+        ),
+        Or<(
+            Changed<MassFlag>,
+            Changed<InertialFlag>,
+            Changed<AdditionalMassProperties>,
+        )>,
+    >,
+    children: Query<&Children>,
+    colliders: Query<Option<&ColliderMassProperties>, With<ColliderFlag>>,
+    bodies: Query<(), With<RigidBodyFlag>>,
+    mut commands: Commands,
+) {
+    for (e, mass, inertial, coordinates, mass_properties) in links.iter() {
+        if mass.mass <= 0.0 {
+            continue;
+        }
+        let (principal_inertia_local_frame, principal_inertia) =
+            inertial.principal_inertia(coordinates.copied().unwrap_or_default());
+        let urdf_mass_properties = AdditionalMassProperties::MassProperties(MassProperties {
+            local_center_of_mass: inertial.center_of_mass.translation,
+            mass: mass.mass,
+            principal_inertia_local_frame,
+            principal_inertia,
+        });
+        if mass_properties != Some(&urdf_mass_properties) {
+            commands.entity(e).insert(urdf_mass_properties);
+        }
+
+        // the link's colliders, and those of links merged into it, down to the next body.
+        let mut unvisited = children
+            .get(e)
+            .map(|children| children.to_vec())
+            .unwrap_or_default();
+        while let Some(child) = unvisited.pop() {
+            if bodies.contains(child) {
+                continue;
+            }
+            if let Ok(collider_mass_properties) = colliders.get(child) {
+                if collider_mass_properties != Some(&ColliderMassProperties::Density(0.0)) {
+                    commands
+                        .entity(child)
+                        .insert(ColliderMassProperties::Density(0.0));
+                }
+            }
+            if let Ok(grandchildren) = children.get(child) {
+                unvisited.extend(grandchildren.iter().copied());
+            }
+        }
+    }
+}
+
+/// writes the robots requested by [`SaveUrdfRequest`]s out, on the [`IoTaskPool`] so saving doesn't hold up
+/// the frame.
+pub fn save_requested_urdfs(
+    mut save_requests: EventReader<SaveUrdfRequest>,
+    links: Query<LinkQuery>,
+    asset_server: Res<AssetServer>,
+) {
+    if save_requests.is_empty() {
+        return;
+    }
+    let urdfs = Urdf::into_hashmap(links);
+    for request in save_requests.read() {
+        let Some(urdf) = urdfs.get(&request.structure).cloned() else {
+            warn!(
+                "can't save {:#?}, no structure with that name is spawned",
+                request.structure
+            );
+            continue;
+        };
+        let asset_server = asset_server.clone();
+        let request = request.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(err) = save_urdf(&asset_server, urdf, &request.path).await {
+                    warn!(
+                        "failed to save {:#?} to {:#?}: {}",
+                        request.structure, request.path, err
+                    );
+                }
+            })
+            .detach();
+    }
+}
+
+/// keeps each [`MimicJoint`]'s motor targets at `multiplier * leader + offset`.
+pub fn apply_mimic_joints(mimics: Query<(Entity, &MimicJoint)>, mut joints: Query<&mut JointFlag>) {
+    for (follower, mimic) in mimics.iter() {
+        let Ok(leader) = joints.get(mimic.leader) else {
+            continue;
+        };
+        let Some(leader_motor) = driven_motor(leader).map(|i| leader.motors[i].clone()) else {
+            continue;
+        };
+        let Ok(mut joint) = joints.get_mut(follower) else {
+            continue;
+        };
+        let Some(i) = driven_motor(&joint) else {
+            continue;
+        };
+        let target_pos = (mimic.multiplier * leader_motor.target_pos as f64 + mimic.offset) as f32;
+        let target_vel = (mimic.multiplier * leader_motor.target_vel as f64) as f32;
+        let motor = &joint.motors[i];
+        if motor.target_pos != target_pos || motor.target_vel != target_vel {
+            let motor = &mut joint.motors[i];
+            motor.target_pos = target_pos;
+            motor.target_vel = target_vel;
+        }
+    }
+}
+
+/// keeps joint motors within their [`JointSafetyController`]'s soft limits: position targets within the soft
+/// limits, and velocity targets within the bounds `k_position` gives at the joint's position, as measured from the
+/// global transforms of its links.
+///
+/// Motors without stiffness don't act on their position target, so it's left alone on those.
+pub fn apply_safety_controllers(
+    mut joints: Query<(&mut JointFlag, &JointSafetyController, &GlobalTransform)>,
+    links: Query<&GlobalTransform>,
+) {
+    for (mut joint, safety_controller, child) in joints.iter_mut() {
+        let Some(i) = driven_motor(&joint) else {
+            continue;
+        };
+        let position = joint
+            .parent_id
+            .and_then(|parent| links.get(parent).ok())
+            .and_then(|parent| joint_position(&joint, parent, child));
+        let motor = &joint.motors[i];
+        let target_pos = if motor.stiffness != 0.0 {
+            (motor.target_pos as f64).clamp(
+                safety_controller.soft_lower_limit,
+                safety_controller
+                    .soft_upper_limit
+                    .max(safety_controller.soft_lower_limit),
+            ) as f32
+        } else {
+            motor.target_pos
+        };
+        let target_vel = match position {
+            Some(position) => {
+                let (min_vel, max_vel) = safety_controller.velocity_bounds(position);
+                (motor.target_vel as f64).clamp(min_vel, max_vel.max(min_vel)) as f32
+            }
+            None => motor.target_vel,
+        };
+        if motor.target_pos != target_pos || motor.target_vel != target_vel {
+            let motor = &mut joint.motors[i];
+            motor.target_pos = target_pos;
+            motor.target_vel = target_vel;
+        }
+    }
+}
+
+/// keeps each joint's motor within its `<limit effort velocity>`. A limit of 0 is taken as no limit, the way urdfs
+/// leave them out on joints that don't need them.
+pub fn clamp_motors_to_joint_limits(mut joints: Query<&mut JointFlag>) {
+    for mut joint in joints.iter_mut() {
+        let Some(i) = driven_motor(&joint) else {
+            continue;
+        };
+        let velocity = joint.limit.velocity as f32;
+        let effort = joint.limit.effort as f32;
+        let motor = &joint.motors[i];
+        let target_vel = if velocity > 0.0 {
+            motor.target_vel.clamp(-velocity, velocity)
+        } else {
+            motor.target_vel
+        };
+        let max_force = if effort > 0.0 {
+            motor.max_force.min(effort)
+        } else {
+            motor.max_force
+        };
+        if motor.target_vel != target_vel || motor.max_force != max_force {
+            let motor = &mut joint.motors[i];
+            motor.target_vel = target_vel;
+            motor.max_force = max_force;
+        }
+    }
+}
+
+/// hands the motors [`UrdfJointSystems`](crate::plugin::UrdfJointSystems) constrained to rapier's [`ImpulseJoint`]s
+/// directly, so physics steps with this frame's constrained targets whenever the [`JointFlag`] to [`ImpulseJoint`]
+/// sync runs.
+pub fn sync_joint_motors(mut joints: Query<(&JointFlag, &mut ImpulseJoint), Changed<JointFlag>>) {
+    // in `JointAxesMaskWrapper` bit order, see `driven_motor`.
+    const AXES: [JointAxis; 6] = [
+        JointAxis::LinX,
+        JointAxis::LinY,
+        JointAxis::LinZ,
+        JointAxis::AngX,
+        JointAxis::AngY,
+        JointAxis::AngZ,
+    ];
+    for (joint, mut impulse_joint) in joints.iter_mut() {
+        let Some(i) = driven_motor(joint) else {
+            continue;
+        };
+        let (axis, motor) = (AXES[i], &joint.motors[i]);
+        let synced = impulse_joint
+            .data
+            .as_ref()
+            .motor(axis)
+            .is_some_and(|synced| {
+                synced.target_pos == motor.target_pos
+                    && synced.target_vel == motor.target_vel
+                    && synced.stiffness == motor.stiffness
+                    && synced.damping == motor.damping
+                    && synced.max_force == motor.max_force
+            });
+        if !synced {
+            impulse_joint
+                .data
+                .as_mut()
+                .set_motor(
+                    axis,
+                    motor.target_pos,
+                    motor.target_vel,
+                    motor.stiffness,
+                    motor.damping,
+                )
+                .set_motor_max_force(axis, motor.max_force);
+        }
+    }
+}
@@ -1,18 +1,52 @@
+use std::collections::HashMap;
+
 use bevy_color::{Color, LinearRgba};
 use bevy_serialization_extras::prelude::material::MaterialFlag;
 use bevy_serialization_extras::prelude::{
     mesh::{GeometryFile, GeometryFlag, MeshPrimitive},
     FileCheckPicker,
 };
-use bevy_utils::default;
+
+use crate::components::{MaterialName, TextureFile};
 use derive_more::From;
 use glam::Vec3;
 use nalgebra::Vector3;
-use urdf_rs::Visual;
+use urdf_rs::{Collision, Geometry, Material, Visual};
 
 #[derive(From, Clone)]
 pub struct VisualWrapper(Visual);
 
+impl VisualWrapper {
+    /// resolves a material that only references a robot level `<material name="..."/>`(no color or texture of
+    /// its own) to the robot level material of the same name.
+    pub fn with_named_materials(mut self, named_materials: &HashMap<String, Material>) -> Self {
+        if let Some(material) = &mut self.0.material {
+            if material.color.is_none() && material.texture.is_none() {
+                if let Some(named_material) = named_materials.get(&material.name) {
+                    *material = named_material.clone();
+                }
+            }
+        }
+        self
+    }
+
+    /// the name of the visual's `<material>`, if it has one.
+    pub fn material_name(&self) -> Option<MaterialName> {
+        let material = self.0.material.as_ref()?;
+        Some(MaterialName {
+            name: material.name.clone(),
+        })
+    }
+
+    /// the visual's `<texture>`, if its material has one.
+    pub fn texture(&self) -> Option<TextureFile> {
+        let texture = self.0.material.as_ref()?.texture.as_ref()?;
+        Some(TextureFile {
+            source: texture.filename.clone(),
+        })
+    }
+}
+
 impl From<&VisualWrapper> for MaterialFlag {
     fn from(value: &VisualWrapper) -> Self {
         if let Some(material) = &value.0.material {
@@ -35,26 +69,132 @@ impl From<&VisualWrapper> for MaterialFlag {
     }
 }
 
+/// the components a visual's material is spawned as, for converting back to urdf.
+pub struct MaterialFlagsWrapper<'a> {
+    pub material: &'a MaterialFlag,
+    pub texture: Option<&'a TextureFile>,
+}
+
+impl From<&MaterialFlagsWrapper<'_>> for Material {
+    fn from(value: &MaterialFlagsWrapper<'_>) -> Self {
+        let color = LinearRgba::from(value.material.color);
+        Self {
+            name: String::new(),
+            color: Some(urdf_rs::Color {
+                rgba: urdf_rs::Vec4([
+                    color.red.into(),
+                    color.green.into(),
+                    color.blue.into(),
+                    color.alpha.into(),
+                ]),
+            }),
+            texture: value.texture.map(|texture| urdf_rs::Texture {
+                filename: texture.source.clone(),
+            }),
+        }
+    }
+}
+
 impl From<&VisualWrapper> for FileCheckPicker<GeometryFlag, GeometryFile> {
     fn from(value: &VisualWrapper) -> Self {
-        let visual = &value.0;
+        Self::from(&GeometryWrapper::from(value.0.geometry.clone()))
+    }
+}
+
+/// a `<collision>` element. Shares geometry conversion with [`VisualWrapper`], but is spawned without a material.
+#[derive(From, Clone)]
+pub struct CollisionWrapper(Collision);
+
+impl From<&CollisionWrapper> for FileCheckPicker<GeometryFlag, GeometryFile> {
+    fn from(value: &CollisionWrapper) -> Self {
+        Self::from(&GeometryWrapper::from(value.0.geometry.clone()))
+    }
+}
+
+#[derive(From, Clone)]
+pub struct GeometryWrapper(Geometry);
+
+impl GeometryWrapper {
+    /// a mesh's `scale`. Geometry is spawned in its link's urdf axes(see
+    /// [`UrdfTransform::into_geometry_transform`](super::UrdfTransform::into_geometry_transform)), so the scale is kept
+    /// as is. Primitives are sized by their [`GeometryFlag`], so they are never scaled.
+    pub fn scale(&self) -> Vec3 {
+        match &self.0 {
+            Geometry::Mesh {
+                scale: Some(scale), ..
+            } => Vec3::new(scale[0] as f32, scale[1] as f32, scale[2] as f32),
+            _ => Vec3::ONE,
+        }
+    }
+}
+
+/// the components a `<geometry>` is spawned as, for converting back to urdf.
+pub struct GeometryFlagsWrapper<'a> {
+    pub primitive: Option<&'a GeometryFlag>,
+    pub file: Option<&'a GeometryFile>,
+    /// scale of the entity holding the geometry, see [`GeometryWrapper::scale`].
+    pub scale: Vec3,
+}
+
+impl GeometryFlagsWrapper<'_> {
+    /// the urdf geometry these flags were spawned from. Files take priority over primitives.
+    pub fn geometry(&self) -> Option<Geometry> {
+        if let Some(file) = self.file {
+            let scale = if self.scale == Vec3::ONE {
+                None
+            } else {
+                Some(urdf_rs::Vec3([
+                    self.scale.x.into(),
+                    self.scale.y.into(),
+                    self.scale.z.into(),
+                ]))
+            };
+            return Some(Geometry::Mesh {
+                filename: file.source.clone(),
+                scale,
+            });
+        }
+        let geometry = match self.primitive?.primitive {
+            MeshPrimitive::Cuboid { size } => Geometry::Box {
+                size: urdf_rs::Vec3(size.map(|n| n.into())),
+            },
+            MeshPrimitive::Cylinder { radius, length } => Geometry::Cylinder {
+                radius: radius.into(),
+                length: length.into(),
+            },
+            MeshPrimitive::Capsule { radius, length } => Geometry::Capsule {
+                radius: radius.into(),
+                length: length.into(),
+            },
+            MeshPrimitive::Sphere { radius } => Geometry::Sphere {
+                radius: radius.into(),
+            },
+        };
+        Some(geometry)
+    }
+}
+
+impl From<&GeometryWrapper> for FileCheckPicker<GeometryFlag, GeometryFile> {
+    fn from(value: &GeometryWrapper) -> Self {
         // let urdf_rotation_flipOLD = Matrix3::new(
         //     0.0, 0.0, -1.0,
         //     0.0, 1.0, 0.0,
         //     1.0, 0.0, 0.0,
         // );
+        // geometry is spawned in its link's urdf axes, so boxes line up as they are.
         let box_allign = [
-            Vec3::new(0.0, 0.0, -1.0),
-            Vec3::new(0.0, 1.0, 0.0),
             Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
         ];
+        // urdf cylinders run along z, bevy's along y.
         let cylinder_align = [
-            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
             Vec3::new(0.0, 0.0, 1.0),
-            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
         ];
 
-        let urdf_geometry = &visual.geometry;
+        let urdf_geometry = &value.0;
 
         let flag_geometry = match urdf_geometry {
             urdf_rs::Geometry::Box { size } => {
@@ -87,15 +227,15 @@ impl From<&VisualWrapper> for FileCheckPicker<GeometryFlag, GeometryFile> {
                         radius: *radius as f32,
                         length: *length as f32,
                     },
-                    //FIXME:
-                    ..default()
+                    // urdf capsules run along z like cylinders do.
+                    orientation_matrix: cylinder_align,
                 })
             }
             urdf_rs::Geometry::Sphere { radius } => FileCheckPicker::PureComponent(GeometryFlag {
                 primitive: MeshPrimitive::Sphere {
                     radius: *radius as f32,
                 },
-                ..default()
+                orientation_matrix: box_allign,
             }),
             urdf_rs::Geometry::Mesh { filename, .. } => {
                 //let asset_source = AssetSource::Package(filename.clone());
@@ -1,7 +1,10 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::FRAC_PI_2,
+};
 
 use bevy_core::Name;
-use bevy_render::view::VisibilityBundle;
+use bevy_render::view::{Visibility, VisibilityBundle};
 use bevy_serialization_extras::prelude::{
     colliders::ColliderFlag,
     continous_collision::CcdFlag,
@@ -18,27 +21,65 @@ use bevy_serialization_extras::prelude::{
 };
 use bevy_transform::prelude::*;
 use bevy_utils::prelude::default;
-use glam::{EulerRot, Quat, Vec3};
-use nalgebra::{Matrix3, Vector3};
-use urdf_rs::{Joint, Link, Pose, Robot, Visual};
+use glam::{DMat3, DQuat, DVec3, EulerRot, Vec3};
+use urdf_rs::{
+    Collision, Geometry, Inertia, Inertial, Joint, JointType, Link, Mass, Material, Pose, Robot,
+    Visual,
+};
 
 use derive_more::From;
 
-use bevy_ecs::{prelude::*, query::QueryData};
+use bevy_ecs::{
+    prelude::*,
+    query::QueryData,
+    world::{Command, CommandQueue},
+};
+use bevy_hierarchy::{BuildChildren, Children, Parent};
+use bevy_log::warn;
 
-use crate::loaders::urdf_loader::Urdf;
+use crate::{
+    components::{
+        DetachedJoint, InertialFlag, JointName, JointSafetyController, LinkCollision, LinkInertial,
+        LinkVisual, MaterialName, MergedLink, MimicJoint, RootLink, TextureFile, UrdfCoordinates,
+    },
+    loaders::urdf_loader::Urdf,
+    resources::{JointMotorFallbacks, UrdfSpawnSettings},
+};
 
-use super::material_and_mesh::VisualWrapper;
+use super::material_and_mesh::{
+    GeometryFlagsWrapper, GeometryWrapper, MaterialFlagsWrapper, VisualWrapper,
+};
 
 /// the collection of things that qualify as a "link", in the ROS 2 context.
+///
+/// A link's `<visual>` and `<collision>` elements are child entities of the link, and are matched by this query too.
+/// Those are told apart by their [`LinkVisual`]/[`LinkCollision`] markers.
 #[derive(QueryData)]
 pub struct LinkQuery {
+    pub entity: Entity,
     pub name: Option<&'static Name>,
     pub structure: &'static StructureFlag,
     pub inertial: Option<&'static MassFlag>,
-    pub visual: FileCheck<GeometryFlag, GeometryFile>,
+    pub inertia: Option<&'static InertialFlag>,
+    pub geometry: Option<&'static GeometryFlag>,
+    pub geometry_file: Option<&'static GeometryFile>,
+    pub material: Option<&'static MaterialFlag>,
+    pub material_name: Option<&'static MaterialName>,
+    pub texture: Option<&'static TextureFile>,
+    pub transform: Option<&'static Transform>,
+    pub coordinates: Option<&'static UrdfCoordinates>,
     pub collision: Option<&'static ColliderFlag>,
     pub joint: Option<&'static JointFlag>,
+    pub joint_name: Option<&'static JointName>,
+    pub mimic: Option<&'static MimicJoint>,
+    pub safety_controller: Option<&'static JointSafetyController>,
+    pub merged: Option<&'static MergedLink>,
+    pub own_inertial: Option<&'static LinkInertial>,
+    pub parent: Option<&'static Parent>,
+    pub children: Option<&'static Children>,
+    pub link_visual: Option<&'static LinkVisual>,
+    pub link_collision: Option<&'static LinkCollision>,
+    pub detached: Option<&'static DetachedJoint>,
 }
 
 impl LazyDeserialize for Urdf {
@@ -48,111 +89,551 @@ impl LazyDeserialize for Urdf {
     }
 }
 
-
+/// spawns every link of the urdf as an entity, with the root link placed at the spawn request's `position`.
+/// Each call spawns an independent copy, so the same urdf can be spawned several times at different poses.
 impl<'a> FromStructure for Urdf {
-    fn into_entities(commands: &mut Commands, value: Self, _: AssetSpawnRequest<Self>) {
-        //let name = request.item.clone();
-        //let robot = value.world_urdfs.get(&request.item).unwrap();
-        //log::info!("urdf is {:#?}", value.clone());
+    fn into_entities(commands: &mut Commands, value: Self, spawn_request: AssetSpawnRequest<Self>) {
+        commands.add(SpawnUrdf {
+            urdf: value,
+            position: spawn_request.position,
+        });
+    }
+}
 
-        let robot = value.robot;
+/// spawns a [`Urdf`] with the [`UrdfSpawnSettings`] in the world at the time the command is applied.
+///
+/// Robots are read back out by structure name, so each spawn gets a structure of its own: the robot's name, or if
+/// that's taken by an earlier spawn, the robot's name followed by `_1`, `_2`, and so on.
+pub struct SpawnUrdf {
+    pub urdf: Urdf,
+    /// world transform of the root link.
+    pub position: Transform,
+}
 
-        let mut structured_link_map = HashMap::new();
-        let mut structured_joint_map = HashMap::new();
-        let mut structured_material_map = HashMap::new();
+impl Command for SpawnUrdf {
+    fn apply(self, world: &mut World) {
+        let settings = world
+            .get_resource::<UrdfSpawnSettings>()
+            .cloned()
+            .unwrap_or_default();
 
-        for joint in &robot.joints {
-            structured_joint_map.insert(joint.child.link.clone(), joint.clone());
-        }
-        for material in &robot.materials {
-            structured_material_map.insert(material.name.clone(), material.clone());
-        }
-        for link in &robot.links {
-            structured_link_map.insert(link.name.clone(), link.clone());
-        }
+        let mut urdf = self.urdf;
+        urdf.robot.name = unique_structure_name(world, &urdf.robot.name);
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+        spawn_urdf(&mut commands, urdf, self.position, &settings);
+        queue.apply(world);
+    }
+}
+
+/// `name`, or `name` with the first numbered suffix no spawned structure uses yet.
+fn unique_structure_name(world: &mut World, name: &str) -> String {
+    let taken = world
+        .query::<&StructureFlag>()
+        .iter(world)
+        .map(|structure| structure.name.clone())
+        .collect::<HashSet<_>>();
+    std::iter::once(name.to_owned())
+        .chain((1..).map(|i| format!("{name}_{i}")))
+        .find(|name| !taken.contains(name))
+        .unwrap()
+}
+
+/// spawns the links and joints of a urdf, see [`UrdfSpawnSettings`] for how the spawned entities are laid out.
+pub fn spawn_urdf(
+    commands: &mut Commands,
+    value: Urdf,
+    position: Transform,
+    settings: &UrdfSpawnSettings,
+) {
+    //let name = request.item.clone();
+    //let robot = value.world_urdfs.get(&request.item).unwrap();
+    //log::info!("urdf is {:#?}", value.clone());
+
+    let robot = value.robot;
+
+    let mut structured_link_map = HashMap::new();
+    let mut structured_joint_map = HashMap::new();
+    let mut structured_material_map = HashMap::new();
+
+    for joint in &robot.joints {
+        structured_joint_map.insert(joint.child.link.clone(), joint.clone());
+    }
+    for material in &robot.materials {
+        structured_material_map.insert(material.name.clone(), material.clone());
+    }
+    for link in &robot.links {
+        structured_link_map.insert(link.name.clone(), link.clone());
+    }
+
+    // structured_linkage_map.insert(UrdfLinkage {
+    //     link:
+    // })
+    // let query_items =structured_link_map.iter().map(|(key, link)|
+    //     {
+    //         LinkQueryItem {
+    //             name: Some(&Name::new(link.name.clone())),
+    //             structure: &StructureFlag { name: value.name.clone() },
+    //             inertial: Some(&MassFlag { mass: link.inertial.mass.value as f32}),
+    //             // implement visual properly
+    //             visual: FileCheckItem {component: &GeometryFlag::default(), component_file: None},
+    //             // implement collision properly. Grouped colliders will need to be ignored for the sake of model coherence.
+    //             collision: Some(&ColliderFlag::default()),
+    //             // implement joint loading properly..
+    //             joint: Some(&JointFlag::default()) }
+    //     }
+    // ).collect::<Vec<Self>>();
+    let mut structured_entities_map: HashMap<String, Entity> = HashMap::new();
+    let coordinates = settings.coordinates;
+    let root_transform = position * Transform::from_rotation(coordinates.root_rotation());
+    let link_transforms = link_world_transforms(&robot, root_transform, coordinates);
+    let root_names = root_link_names(&robot);
+    // only links reachable from the root can be parented, anything else would risk a cyclic hierarchy.
+    let detached =
+        |joint: &Joint| settings.detach_floating_joints && joint.joint_type == JointType::Floating;
+    let merged_into = if settings.merge_fixed_joints {
+        fixed_joint_bodies(&robot)
+    } else {
+        HashMap::new()
+    };
+    // merged links have to be descendants of their body's link for their colliders to be part of it.
+    let parented_to_link = |link_name: &String| {
+        (settings.link_hierarchy || merged_into.contains_key(link_name))
+            && structured_joint_map
+                .get(link_name)
+                .is_some_and(|joint| !detached(joint))
+            && link_transforms.contains_key(link_name)
+    };
+
+    for link in robot.links.iter() {
+        structured_entities_map
+            .entry(link.name.clone())
+            .or_insert_with(|| commands.spawn_empty().id());
+    }
+    let inertial_flags = |inertial: &Inertial| {
+        (
+            MassFlag::from(&InertialWrapper::from(inertial.clone())),
+            InertialFlag {
+                center_of_mass: UrdfTransform::from(inertial.origin.clone())
+                    .into_transform(coordinates),
+                ..InertialFlag::from(&InertialWrapper::from(inertial.clone()))
+            },
+        )
+    };
 
-        // structured_linkage_map.insert(UrdfLinkage {
-        //     link:
-        // })
-        // let query_items =structured_link_map.iter().map(|(key, link)|
-        //     {
-        //         LinkQueryItem {
-        //             name: Some(&Name::new(link.name.clone())),
-        //             structure: &StructureFlag { name: value.name.clone() },
-        //             inertial: Some(&MassFlag { mass: link.inertial.mass.value as f32}),
-        //             // implement visual properly
-        //             visual: FileCheckItem {component: &GeometryFlag::default(), component_file: None},
-        //             // implement collision properly. Grouped colliders will need to be ignored for the sake of model coherence.
-        //             collision: Some(&ColliderFlag::default()),
-        //             // implement joint loading properly..
-        //             joint: Some(&JointFlag::default()) }
-        //     }
-        // ).collect::<Vec<Self>>();
-        let mut structured_entities_map: HashMap<String, Entity> = HashMap::new();
-
-        for (_, link) in structured_link_map.iter() {
-            let e = *structured_entities_map
-                .entry(link.name.clone())
-                .or_insert(commands.spawn_empty().id());
-
-            commands.entity(e)
+    for (_, link) in structured_link_map.iter() {
+        let e = structured_entities_map[&link.name];
+
+        commands
+            .entity(e)
             .insert(Name::new(link.name.clone()))
             //.insert(LinkFlag::from(&link.clone().into()))
-            .insert(StructureFlag { name: robot.name.clone() })
-            .insert(MassFlag {mass: 1.0})
-            //.insert(MassFlag { mass: link.inertial.mass.value as f32})
-            ;
-            if let Some(visual) = link.visual.first() {
-                let visual_wrapper = VisualWrapper::from(visual.clone());
-                match FileCheckPicker::from(&visual_wrapper) {
-                    FileCheckPicker::PureComponent(t) => commands.entity(e).insert(t),
-                    FileCheckPicker::PathComponent(u) => commands.entity(e).insert(u),
-                };
-                commands
-                    .entity(e)
-                    .insert(MaterialFlag::from(&visual_wrapper));
-            }
-            //let mut temp_rotate_for_demo = spawn_request.position;
-            //FIXME: urdf meshes have their verticies re-oriented to match bevy's cordinate system, but their rotation isn't rotated back
-            // to account for this, this will need a proper fix later.
-            //temp_rotate_for_demo.rotate_x(-PI * 0.5);
-
+            .insert(StructureFlag {
+                name: robot.name.clone(),
+            })
+            .insert(coordinates);
+        let (mass, inertial) = inertial_flags(&link.inertial);
+        let merged_links = merged_into
+            .iter()
+            .filter(|(_, (body, _))| body == &link.name)
+            .filter_map(|(name, (_, transform))| {
+                Some((*transform, &structured_link_map.get(name)?.inertial))
+            })
+            .collect::<Vec<_>>();
+        let merged_body = merged_into
+            .get(&link.name)
+            .and_then(|(body, _)| structured_entities_map.get(body));
+        if let Some(&body) = merged_body {
             commands
                 .entity(e)
+                .insert(MergedLink { body })
+                .insert(LinkInertial {
+                    mass: mass.mass,
+                    inertial,
+                });
+        } else if !merged_links.is_empty() {
+            let body_inertial = combined_inertial(
+                std::iter::once((Transform::IDENTITY, &link.inertial)).chain(merged_links),
+            );
+            let (body_mass, body_inertia) = inertial_flags(&body_inertial);
+            commands
+                .entity(e)
+                .insert(body_mass)
+                .insert(body_inertia)
+                .insert(LinkInertial {
+                    mass: mass.mass,
+                    inertial,
+                });
+        } else {
+            commands.entity(e).insert(mass).insert(inertial);
+        }
+        // each visual is its own child entity so links built from several visuals keep their individual
+        // origins and materials.
+        for visual in link.visual.iter() {
+            let visual_wrapper =
+                VisualWrapper::from(visual.clone()).with_named_materials(&structured_material_map);
+            let visual_e = commands
+                .spawn(LinkVisual)
+                .insert(StructureFlag {
+                    name: robot.name.clone(),
+                })
+                .insert(coordinates)
+                .insert(MaterialFlag::from(&visual_wrapper))
                 .insert(VisibilityBundle::default())
                 .insert(TransformBundle {
-                    //local: temp_rotate_for_demo,
+                    local: UrdfTransform::from(visual.origin.clone())
+                        .into_geometry_transform(coordinates)
+                        .with_scale(GeometryWrapper::from(visual.geometry.clone()).scale()),
                     ..default()
                 })
-                .insert(ColliderFlag::default())
-                .insert(SolverGroupsFlag {
-                    memberships: GroupWrapper::GROUP_1,
-                    filters: GroupWrapper::GROUP_2,
+                .id();
+            match FileCheckPicker::from(&visual_wrapper) {
+                FileCheckPicker::PureComponent(t) => commands.entity(visual_e).insert(t),
+                FileCheckPicker::PathComponent(u) => commands.entity(visual_e).insert(u),
+            };
+            commands
+                .entity(visual_e)
+                .insert(GeometryShiftMarked::default());
+            // only named in bevy when named in the urdf, so unnamed visuals are saved back out unnamed.
+            if let Some(name) = &visual.name {
+                commands.entity(visual_e).insert(Name::new(name.clone()));
+            }
+            if let Some(material_name) = visual_wrapper.material_name() {
+                commands.entity(visual_e).insert(material_name);
+            }
+            if let Some(texture) = visual_wrapper.texture() {
+                commands.entity(visual_e).insert(texture);
+            }
+            commands.entity(e).add_child(visual_e);
+        }
+        // collision geometry is usually a simplified stand-in for the visuals, so it gets its own hidden
+        // child entities. Colliders on children are attached to the link's rigidbody.
+        for collision in link.collision.iter() {
+            let collision_e = spawn_collider(
+                commands,
+                &collision.origin,
+                &collision.geometry,
+                coordinates,
+            );
+            commands
+                .entity(collision_e)
+                .insert(LinkCollision)
+                .insert(StructureFlag {
+                    name: robot.name.clone(),
                 })
-                .insert(GeometryShiftMarked::default())
+                .insert(coordinates);
+            if let Some(name) = &collision.name {
+                commands.entity(collision_e).insert(Name::new(name.clone()));
+            }
+            commands.entity(e).add_child(collision_e);
+        }
+        // stand-ins aren't part of the urdf, so they aren't marked as a `LinkCollision` and aren't saved back out.
+        if settings.colliders_from_visuals && link.collision.is_empty() {
+            for visual in link.visual.iter() {
+                let collider_e =
+                    spawn_collider(commands, &visual.origin, &visual.geometry, coordinates);
+                commands.entity(e).add_child(collider_e);
+            }
+        }
+        let link_transform = if parented_to_link(&link.name) {
+            UrdfTransform::from(structured_joint_map[&link.name].origin.clone())
+                .into_transform(coordinates)
+        } else {
+            // links the joint tree can't reach(malformed urdfs) are left at the root.
+            link_transforms
+                .get(&link.name)
+                .copied()
+                .unwrap_or(root_transform)
+        };
+        if root_names.contains(&link.name) {
+            commands.entity(e).insert(RootLink);
+        }
+
+        commands
+            .entity(e)
+            .insert(VisibilityBundle::default())
+            .insert(TransformBundle {
+                local: link_transform,
+                ..default()
+            });
+        if !merged_into.contains_key(&link.name) {
+            commands
+                .entity(e)
                 .insert(RigidBodyFlag::Dynamic)
                 .insert(CcdFlag::default());
         }
+    }
 
-        for (_, joint) in structured_joint_map.iter() {
-            let e = *structured_entities_map
-                .entry(joint.child.link.clone())
-                .or_insert(commands.spawn_empty().id());
+    for (_, joint) in structured_joint_map.iter() {
+        let Some(&e) = structured_entities_map.get(&joint.child.link) else {
+            continue;
+        };
+        commands.entity(e).insert(JointName {
+            name: joint.name.clone(),
+        });
+        if detached(joint) {
+            if let Some(&parent) = structured_entities_map.get(&joint.parent.link) {
+                commands.entity(e).insert(DetachedJoint {
+                    parent,
+                    origin: UrdfTransform::from(joint.origin.clone()).into_transform(coordinates),
+                });
+            }
+            continue;
+        }
+        if merged_into.contains_key(&joint.child.link) {
+            // merged links move with their body, the hierarchy stands in for the joint.
+            if let Some(parent) = structured_entities_map.get(&joint.parent.link) {
+                commands.entity(*parent).add_child(e);
+            }
+            continue;
+        }
 
-            //log::info!("spawning joint on {:#?}", e);
-            let new_joint = JointFlag::from(&JointWrapper::from(joint.clone()));
+        //log::info!("spawning joint on {:#?}", e);
+        let mut new_joint = JointFlag::from(&JointWrapper::from(joint.clone()));
+        let (local_frame1, local_frame2) = joint_frames(joint, coordinates);
+        new_joint.local_frame1 = local_frame1;
+        new_joint.local_frame2 = Some(local_frame2);
+        let motor = joint_motor(joint, &settings.motor_fallbacks);
+        new_joint.motors = std::array::from_fn(|_| motor.clone());
+        // merged links have no rigid body to hold onto, so joints onto them hold onto their body instead, from
+        // the merged link's pose in it.
+        let parent_link = match merged_into.get(&joint.parent.link) {
+            Some((body, parent_in_body)) => {
+                let parent_in_body =
+                    UrdfTransform::from_transform(parent_in_body, UrdfCoordinates::Untouched)
+                        .into_transform(coordinates);
+                new_joint.local_frame1 = parent_in_body * new_joint.local_frame1;
+                body
+            }
+            None => &joint.parent.link,
+        };
+        // bind to the parent link of *this* spawn directly. Resolving by name alone is ambiguous
+        // when the same urdf is spawned more than once.
+        new_joint.parent_id = structured_entities_map.get(parent_link).copied();
 
-            commands
-                .entity(e)
-                .insert(new_joint)
-                .insert(RigidBodyFlag::Dynamic);
+        commands
+            .entity(e)
+            .insert(new_joint)
+            .insert(RigidBodyFlag::Dynamic);
+
+        if let Some(safety_controller) = &joint.safety_controller {
+            commands.entity(e).insert(JointSafetyController {
+                soft_lower_limit: safety_controller.soft_lower_limit,
+                soft_upper_limit: safety_controller.soft_upper_limit,
+                k_position: safety_controller.k_position,
+                k_velocity: safety_controller.k_velocity,
+            });
+        }
+
+        if let Some(mimic) = &joint.mimic {
+            let leader = robot
+                .joints
+                .iter()
+                .find(|leader| leader.name == mimic.joint)
+                .and_then(|leader| structured_entities_map.get(&leader.child.link));
+            match leader {
+                Some(leader) => {
+                    commands.entity(e).insert(MimicJoint {
+                        leader: *leader,
+                        multiplier: mimic.multiplier.unwrap_or(1.0),
+                        offset: mimic.offset.unwrap_or(0.0),
+                    });
+                }
+                None => warn!(
+                    "{:#?} mimics {:#?}, which isn't a joint of {:#?}. Ignoring its <mimic>",
+                    joint.name, mimic.joint, robot.name
+                ),
+            }
+        }
+
+        if parented_to_link(&joint.child.link) {
+            if let Some(parent) = structured_entities_map.get(&joint.parent.link) {
+                commands.entity(*parent).add_child(e);
+            }
+        }
+    }
+}
+
+/// links joined to their parent by a fixed joint, mapped to the link whose rigid body they merge into(the nearest
+/// ancestor that isn't joined by a fixed joint), and their pose in that link's urdf frame.
+///
+/// Fixed joints onto links the urdf doesn't declare aren't merged, there is no body to merge into.
+pub fn fixed_joint_bodies(robot: &Robot) -> HashMap<String, (String, Transform)> {
+    let mut bodies = HashMap::new();
+    for link in &robot.links {
+        let mut body = &link.name;
+        let mut transform = Transform::IDENTITY;
+        // bounded by the joint count, so cyclic urdfs can't loop forever.
+        for _ in 0..robot.joints.len() {
+            let Some(joint) = robot.joints.iter().find(|joint| {
+                &joint.child.link == body
+                    && joint.joint_type == JointType::Fixed
+                    && robot
+                        .links
+                        .iter()
+                        .any(|link| link.name == joint.parent.link)
+            }) else {
+                break;
+            };
+            transform = UrdfTransform::from(joint.origin.clone())
+                .into_transform(UrdfCoordinates::Untouched)
+                * transform;
+            body = &joint.parent.link;
+        }
+        if body != &link.name {
+            bodies.insert(link.name.clone(), (body.clone(), transform));
+        }
+    }
+    bodies
+}
+
+/// the mass and inertia of several links as one body, from each link's inertial and its pose in the body's urdf
+/// frame.
+pub fn combined_inertial<'a>(
+    parts: impl IntoIterator<Item = (Transform, &'a Inertial)>,
+) -> Inertial {
+    // (mass, center of mass, inertia tensor at the center of mass), all in the body's frame.
+    let parts = parts
+        .into_iter()
+        .map(|(transform, inertial)| {
+            let center_of_mass = transform
+                * UrdfTransform::from(inertial.origin.clone())
+                    .into_transform(UrdfCoordinates::Untouched);
+            let rotation = DMat3::from_quat(center_of_mass.rotation.as_dquat());
+            let inertia = &inertial.inertia;
+            let tensor = DMat3::from_cols(
+                DVec3::new(inertia.ixx, inertia.ixy, inertia.ixz),
+                DVec3::new(inertia.ixy, inertia.iyy, inertia.iyz),
+                DVec3::new(inertia.ixz, inertia.iyz, inertia.izz),
+            );
+            (
+                inertial.mass.value,
+                center_of_mass.translation.as_dvec3(),
+                rotation * tensor * rotation.transpose(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mass = parts.iter().map(|(mass, ..)| mass).sum::<f64>();
+    let center_of_mass = if mass > 0.0 {
+        parts
+            .iter()
+            .map(|(mass, position, _)| *mass * *position)
+            .sum::<DVec3>()
+            / mass
+    } else {
+        DVec3::ZERO
+    };
+    // parallel axis theorem, to move every part's tensor to the combined center of mass.
+    let tensor = parts
+        .iter()
+        .map(|(mass, position, tensor)| {
+            let offset = *position - center_of_mass;
+            let shift = DMat3::from_diagonal(DVec3::splat(offset.length_squared()))
+                - DMat3::from_cols(offset * offset.x, offset * offset.y, offset * offset.z);
+            *tensor + shift * *mass
+        })
+        .fold(DMat3::ZERO, |sum, tensor| sum + tensor);
+
+    Inertial {
+        origin: Pose {
+            xyz: urdf_rs::Vec3(center_of_mass.to_array()),
+            rpy: urdf_rs::Vec3([0.0; 3]),
+        },
+        mass: Mass { value: mass },
+        inertia: Inertia {
+            ixx: tensor.x_axis.x,
+            ixy: tensor.y_axis.x,
+            ixz: tensor.z_axis.x,
+            iyy: tensor.y_axis.y,
+            iyz: tensor.z_axis.y,
+            izz: tensor.z_axis.z,
+        },
+    }
+}
+
+/// names of the links that are not the child of any joint. A well formed urdf has exactly one.
+pub fn root_link_names(robot: &Robot) -> Vec<String> {
+    robot
+        .links
+        .iter()
+        .filter(|link| {
+            !robot
+                .joints
+                .iter()
+                .any(|joint| joint.child.link == link.name)
+        })
+        .map(|link| link.name.clone())
+        .collect()
+}
+
+/// walks the joint tree down from the root link(s), composing each joint's origin onto its parent link's
+/// transform to get the world transform of every link. Root links are placed at `root`.
+pub fn link_world_transforms(
+    robot: &Robot,
+    root: Transform,
+    coordinates: UrdfCoordinates,
+) -> HashMap<String, Transform> {
+    let mut joints_by_parent: HashMap<&str, Vec<&Joint>> = HashMap::new();
+    for joint in &robot.joints {
+        joints_by_parent
+            .entry(joint.parent.link.as_str())
+            .or_default()
+            .push(joint);
+    }
+
+    let mut link_transforms = HashMap::new();
+    let mut unvisited = Vec::new();
+    for root_name in root_link_names(robot) {
+        link_transforms.insert(root_name.clone(), root);
+        unvisited.push(root_name);
+    }
+
+    while let Some(parent_name) = unvisited.pop() {
+        let parent_transform = link_transforms[&parent_name];
+        for joint in joints_by_parent
+            .get(parent_name.as_str())
+            .into_iter()
+            .flatten()
+        {
+            // guard against cyclic "trees" from malformed urdfs.
+            if link_transforms.contains_key(&joint.child.link) {
+                continue;
+            }
+            let joint_offset =
+                UrdfTransform::from(joint.origin.clone()).into_transform(coordinates);
+            link_transforms.insert(joint.child.link.clone(), parent_transform * joint_offset);
+            unvisited.push(joint.child.link.clone());
         }
     }
+    link_transforms
 }
 
 impl IntoHashMap<Query<'_, '_, LinkQuery>> for Urdf {
     fn into_hashmap(value: Query<'_, '_, LinkQuery>) -> HashMap<String, Self> {
         let mut urdf_map = HashMap::new();
+        // link entity -> (structure, link name), for attaching visuals and collisions to their link.
+        let mut link_names: HashMap<Entity, (String, String)> = HashMap::new();
+        // (link entity, element entity, element), for attaching visuals and collisions to their link.
+        let mut visuals: Vec<(Entity, Entity, Visual)> = Vec::new();
+        let mut collisions: Vec<(Entity, Entity, Collision)> = Vec::new();
+        // link entity -> its children, to keep visuals and collisions in the order they were spawned in.
+        let mut link_children: HashMap<Entity, Vec<Entity>> = HashMap::new();
+        let mut mimics: Vec<(Entity, MimicJoint)> = Vec::new();
+        // link -> (parent link, origin, coordinates, joint name, joint type), for the joints links are spawned without
+        // a JointFlag for: the fixed joints of merged links, and detached floating joints.
+        let mut unflagged_joints: Vec<(
+            Entity,
+            Entity,
+            Transform,
+            UrdfCoordinates,
+            Option<String>,
+            JointType,
+        )> = Vec::new();
+        // joint's child link -> (joint origin, coordinates).
+        let mut joint_origins: Vec<(Entity, Transform, UrdfCoordinates)> = Vec::new();
+
         for link in value.iter() {
             let structure_name = link.structure.name.clone();
             let entry = urdf_map.entry(structure_name.clone()).or_insert(Urdf {
@@ -164,30 +645,96 @@ impl IntoHashMap<Query<'_, '_, LinkQuery>> for Urdf {
                 },
             });
 
+            let transform = link.transform.copied().unwrap_or_default();
+            let coordinates = link.coordinates.copied().unwrap_or_default();
+            let geometry = GeometryFlagsWrapper {
+                primitive: link.geometry,
+                file: link.geometry_file,
+                scale: transform.scale,
+            }
+            .geometry();
+            let element_name = link.name.map(|name| name.to_string());
+
+            if link.link_visual.is_some() {
+                let (Some(parent), Some(geometry)) = (link.parent, geometry) else {
+                    continue;
+                };
+                visuals.push((
+                    parent.get(),
+                    link.entity,
+                    Visual {
+                        name: element_name,
+                        origin: UrdfTransform::from_geometry_transform(&transform, coordinates)
+                            .into(),
+                        geometry,
+                        // visuals spawned without a `<material>` only have a material to render with.
+                        material: link.material.zip(link.material_name).map(
+                            |(material, material_name)| Material {
+                                name: material_name.name.clone(),
+                                ..Material::from(&MaterialFlagsWrapper {
+                                    material,
+                                    texture: link.texture,
+                                })
+                            },
+                        ),
+                    },
+                ));
+                continue;
+            }
+            if link.link_collision.is_some() {
+                let (Some(parent), Some(geometry)) = (link.parent, geometry) else {
+                    continue;
+                };
+                collisions.push((
+                    parent.get(),
+                    link.entity,
+                    Collision {
+                        name: element_name,
+                        origin: UrdfTransform::from_geometry_transform(&transform, coordinates)
+                            .into(),
+                        geometry,
+                    },
+                ));
+                continue;
+            }
+
+            let link_name = element_name.unwrap_or(entry.robot.links.len().to_string());
+            link_names.insert(link.entity, (structure_name.clone(), link_name.clone()));
+            if let Some(children) = link.children {
+                link_children.insert(link.entity, children.to_vec());
+            }
+            entry.robot.links.push(Link {
+                name: link_name.clone(),
+                inertial: Inertial::from(&match link.own_inertial {
+                    Some(own) => InertialFlagsWrapper {
+                        mass: own.mass,
+                        inertial: own.inertial,
+                        coordinates,
+                    },
+                    None => InertialFlagsWrapper {
+                        mass: link.inertial.map(|flag| flag.mass).unwrap_or_default(),
+                        inertial: link.inertia.copied().unwrap_or_default(),
+                        coordinates,
+                    },
+                }),
+                visual: Vec::new(),
+                collision: Vec::new(),
+            });
+
             match link.joint {
                 Some(joint) => {
-                    let link_name = link
-                        .name
-                        .unwrap_or(&Name::new(entry.robot.joints.len().to_string()))
-                        .to_string();
-                    let joint_name = link_name.clone() + "_joint";
+                    joint_origins.push((link.entity, joint_origin(joint), coordinates));
+                    let joint_name = link
+                        .joint_name
+                        .map(|joint_name| joint_name.name.clone())
+                        .unwrap_or(link_name.clone() + "_joint");
                     let joint_parent = joint.parent_name.clone().unwrap_or_default();
                     //let urdf_link_name = link_name + "_link";
                     entry.robot.joints.push(Joint {
                         name: joint_name,
-                        //FIXME:  implement this properly have this be a consequence of joint data via a function. This is a placeholder.
-                        joint_type: urdf_rs::JointType::Continuous,
-                        origin: Pose {
-                            xyz: urdf_rs::Vec3([
-                                joint.local_frame1.translation.x.into(),
-                                joint.local_frame1.translation.y.into(),
-                                joint.local_frame1.translation.z.into(),
-                            ]),
-                            rpy: {
-                                let rot = joint.local_frame1.rotation.to_euler(EulerRot::XYZ);
-                                urdf_rs::Vec3([rot.0.into(), rot.1.into(), rot.2.into()])
-                            },
-                        },
+                        joint_type: urdf_joint_type(joint),
+                        origin: UrdfTransform::from_transform(&joint_origin(joint), coordinates)
+                            .into(),
                         parent: urdf_rs::LinkName {
                             link: joint_parent.clone(),
                         },
@@ -195,38 +742,384 @@ impl IntoHashMap<Query<'_, '_, LinkQuery>> for Urdf {
                             link: link_name.clone(),
                         },
                         axis: urdf_rs::Axis {
-                            xyz: {
-                                let x = joint.limit_axes.contains(JointAxesMaskWrapper::ANG_X)
-                                    as u32 as f64;
-                                let y = joint.limit_axes.contains(JointAxesMaskWrapper::ANG_Y)
-                                    as u32 as f64;
-                                let z = joint.limit_axes.contains(JointAxesMaskWrapper::ANG_Z)
-                                    as u32 as f64;
-                                urdf_rs::Vec3([x, y, z])
-                            },
+                            xyz: urdf_joint_axis(joint, coordinates),
                         },
                         limit: urdf_rs::JointLimit {
                             lower: joint.limit.lower,
                             upper: joint.limit.upper,
-                            //FIXME: implement this properly
-                            effort: f64::MAX,
-                            //FIXME: implement this properly
-                            velocity: f64::MAX,
+                            effort: joint.limit.effort,
+                            velocity: joint.limit.velocity,
                         },
-                        //FIXME: implement this properly
-                        dynamics: None,
-                        //FIXME: implement this properly
+                        dynamics: (joint.dynamics.damping != 0.0 || joint.dynamics.friction != 0.0)
+                            .then(|| urdf_rs::Dynamics {
+                                damping: joint.dynamics.damping,
+                                friction: joint.dynamics.friction,
+                            }),
+                        // filled in once every link's name is known, see below.
                         mimic: None,
-                        //FIXME: implement this properly
-                        safety_controller: None,
+                        safety_controller: link.safety_controller.map(|safety_controller| {
+                            urdf_rs::SafetyController {
+                                soft_lower_limit: safety_controller.soft_lower_limit,
+                                soft_upper_limit: safety_controller.soft_upper_limit,
+                                k_position: safety_controller.k_position,
+                                k_velocity: safety_controller.k_velocity,
+                            }
+                        }),
                     })
                 }
                 None => {}
             }
+            if let Some(mimic) = link.mimic {
+                mimics.push((link.entity, *mimic));
+            }
+            let joint_name = link.joint_name.map(|joint_name| joint_name.name.clone());
+            if let (Some(_), Some(parent)) = (link.merged, link.parent) {
+                unflagged_joints.push((
+                    link.entity,
+                    parent.get(),
+                    transform,
+                    coordinates,
+                    joint_name.clone(),
+                    JointType::Fixed,
+                ));
+            }
+            if let Some(detached) = link.detached {
+                unflagged_joints.push((
+                    link.entity,
+                    detached.parent,
+                    detached.origin,
+                    coordinates,
+                    joint_name,
+                    JointType::Floating,
+                ));
+            }
+        }
+
+        // query order isn't the order visuals and collisions were spawned in, their link's children are.
+        let spawn_order = |(parent, e): (&Entity, &Entity)| {
+            link_children
+                .get(parent)
+                .and_then(|children| children.iter().position(|child| child == e))
+        };
+        visuals.sort_by_key(|(parent, e, _)| spawn_order((parent, e)));
+        collisions.sort_by_key(|(parent, e, _)| spawn_order((parent, e)));
+        for (parent, _, visual) in visuals {
+            if let Some(link) = exported_link(&mut urdf_map, &link_names, parent) {
+                link.visual.push(visual);
+            }
+        }
+        for (parent, _, collision) in collisions {
+            if let Some(link) = exported_link(&mut urdf_map, &link_names, parent) {
+                link.collision.push(collision);
+            }
+        }
+
+        // joints onto merged links are held by the merged link's body, see `spawn_urdf`. Take the merged link's pose
+        // in its body back out of their origin.
+        let merged_parents = unflagged_joints
+            .iter()
+            .filter(|(.., joint_type)| *joint_type == JointType::Fixed)
+            .map(|(merged, parent, transform, ..)| (*merged, (*parent, *transform)))
+            .collect::<HashMap<_, _>>();
+        for (child, origin, coordinates) in joint_origins {
+            let Some(parent_link) = exported_joint(&mut urdf_map, &link_names, child)
+                .map(|joint| joint.parent.link.clone())
+            else {
+                continue;
+            };
+            let structure_name = &link_names[&child].0;
+            let parent_key = (structure_name.clone(), parent_link);
+            let Some(mut merged) = merged_parents
+                .keys()
+                .find(|merged| link_names.get(merged) == Some(&parent_key))
+                .copied()
+            else {
+                continue;
+            };
+            let mut pose_in_body = Transform::IDENTITY;
+            for _ in 0..merged_parents.len() {
+                let Some((parent, transform)) = merged_parents.get(&merged) else {
+                    break;
+                };
+                pose_in_body = *transform * pose_in_body;
+                merged = *parent;
+            }
+            let origin = Transform::from_matrix(pose_in_body.compute_matrix().inverse()) * origin;
+            if let Some(joint) = exported_joint(&mut urdf_map, &link_names, child) {
+                joint.origin = UrdfTransform::from_transform(&origin, coordinates).into();
+            }
+        }
+
+        for (child, parent, origin, coordinates, joint_name, joint_type) in unflagged_joints {
+            let (Some((structure_name, link_name)), Some((_, parent_name))) =
+                (link_names.get(&child), link_names.get(&parent))
+            else {
+                continue;
+            };
+            let Some(urdf) = urdf_map.get_mut(structure_name) else {
+                continue;
+            };
+            urdf.robot.joints.push(Joint {
+                name: joint_name.unwrap_or(link_name.clone() + "_joint"),
+                joint_type,
+                origin: UrdfTransform::from_transform(&origin, coordinates).into(),
+                parent: urdf_rs::LinkName {
+                    link: parent_name.clone(),
+                },
+                child: urdf_rs::LinkName {
+                    link: link_name.clone(),
+                },
+                axis: urdf_rs::Axis {
+                    xyz: urdf_rs::Vec3([1.0, 0.0, 0.0]),
+                },
+                limit: urdf_rs::JointLimit::default(),
+                dynamics: None,
+                mimic: None,
+                safety_controller: None,
+            });
+        }
+
+        for (follower, mimic) in mimics {
+            let Some(leader_joint) = exported_joint(&mut urdf_map, &link_names, mimic.leader)
+                .map(|leader| leader.name.clone())
+            else {
+                continue;
+            };
+            if let Some(joint) = exported_joint(&mut urdf_map, &link_names, follower) {
+                joint.mimic = Some(urdf_rs::Mimic {
+                    joint: leader_joint,
+                    multiplier: Some(mimic.multiplier),
+                    offset: Some(mimic.offset),
+                });
+            }
+        }
+
+        for urdf in urdf_map.values_mut() {
+            // query order isn't stable, so sort to keep the written urdf stable.
+            urdf.robot.links.sort_by(|a, b| a.name.cmp(&b.name));
+            urdf.robot.joints.sort_by(|a, b| a.name.cmp(&b.name));
+            declare_robot_materials(&mut urdf.robot);
         }
         urdf_map
     }
 }
+
+fn exported_link<'a>(
+    urdf_map: &'a mut HashMap<String, Urdf>,
+    link_names: &HashMap<Entity, (String, String)>,
+    link_entity: Entity,
+) -> Option<&'a mut Link> {
+    let (structure_name, link_name) = link_names.get(&link_entity)?;
+    urdf_map
+        .get_mut(structure_name)?
+        .robot
+        .links
+        .iter_mut()
+        .find(|link| &link.name == link_name)
+}
+
+/// spawns a hidden collider for `geometry` at `origin`, to be added as a child of its link.
+fn spawn_collider(
+    commands: &mut Commands,
+    origin: &Pose,
+    geometry: &Geometry,
+    coordinates: UrdfCoordinates,
+) -> Entity {
+    let geometry = GeometryWrapper::from(geometry.clone());
+    let collider_e = commands
+        .spawn(VisibilityBundle {
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(TransformBundle {
+            local: UrdfTransform::from(origin.clone())
+                .into_geometry_transform(coordinates)
+                .with_scale(geometry.scale()),
+            ..default()
+        })
+        .insert(ColliderFlag::default())
+        .insert(SolverGroupsFlag {
+            memberships: GroupWrapper::GROUP_1,
+            filters: GroupWrapper::GROUP_2,
+        })
+        .insert(GeometryShiftMarked::default())
+        .id();
+    match FileCheckPicker::from(&geometry) {
+        FileCheckPicker::PureComponent(t) => commands.entity(collider_e).insert(t),
+        FileCheckPicker::PathComponent(u) => commands.entity(collider_e).insert(u),
+    };
+    collider_e
+}
+
+/// the exported joint whose child is the link spawned as `link_entity`.
+fn exported_joint<'a>(
+    urdf_map: &'a mut HashMap<String, Urdf>,
+    link_names: &HashMap<Entity, (String, String)>,
+    link_entity: Entity,
+) -> Option<&'a mut Joint> {
+    let (structure_name, link_name) = link_names.get(&link_entity)?;
+    urdf_map
+        .get_mut(structure_name)?
+        .robot
+        .joints
+        .iter_mut()
+        .find(|joint| &joint.child.link == link_name)
+}
+
+/// moves the inline materials of a robot's visuals into its robot level material table under their own names,
+/// with each visual referencing its material by name. Materials sharing a name with a different declared material
+/// are left inline, as urdf allows.
+fn declare_robot_materials(robot: &mut Robot) {
+    let same_material = |a: &Material, b: &Material| {
+        a.color.as_ref().map(|color| color.rgba.0) == b.color.as_ref().map(|color| color.rgba.0)
+            && a.texture.as_ref().map(|texture| &texture.filename)
+                == b.texture.as_ref().map(|texture| &texture.filename)
+    };
+    for link in robot.links.iter_mut() {
+        for visual in link.visual.iter_mut() {
+            let Some(material) = visual.material.as_mut() else {
+                continue;
+            };
+            if material.name.is_empty() {
+                continue;
+            }
+            match robot
+                .materials
+                .iter()
+                .find(|declared| declared.name == material.name)
+            {
+                Some(declared) if !same_material(declared, material) => continue,
+                Some(_) => {}
+                None => robot.materials.push(material.clone()),
+            }
+            material.color = None;
+            material.texture = None;
+        }
+    }
+    robot.materials.sort_by(|a, b| a.name.cmp(&b.name));
+}
+
+/// the urdf joint type matching the axes a [`JointFlag`] leaves free. Rotational joints with a limit on their free
+/// axis are revolute, otherwise continuous.
+pub fn urdf_joint_type(joint: &JointFlag) -> JointType {
+    let free_axes = joint.locked_axes.complement();
+    let free_linear = free_axes.intersection(
+        JointAxesMaskWrapper::LIN_X | JointAxesMaskWrapper::LIN_Y | JointAxesMaskWrapper::LIN_Z,
+    );
+    let free_angular = free_axes.intersection(
+        JointAxesMaskWrapper::ANG_X | JointAxesMaskWrapper::ANG_Y | JointAxesMaskWrapper::ANG_Z,
+    );
+    match (
+        free_linear.bits().count_ones(),
+        free_angular.bits().count_ones(),
+    ) {
+        (0, 0) => JointType::Fixed,
+        (0, 1) => {
+            if joint.limit_axes.intersects(free_angular) {
+                JointType::Revolute
+            } else {
+                JointType::Continuous
+            }
+        }
+        (1, 0) => JointType::Prismatic,
+        (2, 1) => JointType::Planar,
+        (0, 3) => JointType::Spherical,
+        // anything else has no exact urdf equivalent, so don't constrain it at all.
+        _ => JointType::Floating,
+    }
+}
+
+/// index into [`JointFlag::motors`] of the axis a single axis(revolute, continuous or prismatic) joint moves
+/// along, or `None` for joints that move along more or fewer axes.
+pub fn driven_motor(joint: &JointFlag) -> Option<usize> {
+    let free_axes = joint
+        .locked_axes
+        .complement()
+        .intersection(JointAxesMaskWrapper::LOCKED_FIXED_AXES);
+    match free_axes.bits().count_ones() {
+        1 => Some(free_axes.bits().trailing_zeros() as usize),
+        _ => None,
+    }
+}
+
+/// the position of a single axis joint(see [`driven_motor`]) along its free axis, measured from the global
+/// transforms of its parent and child links: a distance for prismatic joints, an angle in (-π, π] for revolute and
+/// continuous ones.
+pub fn joint_position(
+    joint: &JointFlag,
+    parent: &GlobalTransform,
+    child: &GlobalTransform,
+) -> Option<f64> {
+    let i = driven_motor(joint)?;
+    let frame1 = parent.compute_transform() * joint.local_frame1;
+    let frame2 = child.compute_transform() * joint.local_frame2.unwrap_or_default();
+    let rotation = frame1.rotation.inverse() * frame2.rotation;
+    if i < 3 {
+        let translation = frame1.rotation.inverse() * (frame2.translation - frame1.translation);
+        return Some(translation[i] as f64);
+    }
+    // the twist of the child's frame around the joint's axis.
+    let axis = [Vec3::X, Vec3::Y, Vec3::Z][i - 3];
+    let rotation = if rotation.w < 0.0 {
+        -rotation
+    } else {
+        rotation
+    };
+    Some(2.0 * (rotation.xyz().dot(axis) as f64).atan2(rotation.w as f64))
+}
+
+/// the urdf axis of a [`JointFlag`]: its free axis for revolute, continuous and prismatic joints, and the
+/// plane's normal for planar joints. See [`joint_frames`] for how the axis is stored.
+pub fn urdf_joint_axis(joint: &JointFlag, coordinates: UrdfCoordinates) -> urdf_rs::Vec3 {
+    let free_axes = joint.locked_axes.complement();
+    let axis_of = |x: JointAxesMaskWrapper, y: JointAxesMaskWrapper, z: JointAxesMaskWrapper| {
+        DVec3::new(
+            free_axes.contains(x) as u32 as f64,
+            free_axes.contains(y) as u32 as f64,
+            free_axes.contains(z) as u32 as f64,
+        )
+    };
+    let joint_frame_axis = match urdf_joint_type(joint) {
+        JointType::Prismatic => axis_of(
+            JointAxesMaskWrapper::LIN_X,
+            JointAxesMaskWrapper::LIN_Y,
+            JointAxesMaskWrapper::LIN_Z,
+        ),
+        JointType::Revolute | JointType::Continuous | JointType::Planar => axis_of(
+            JointAxesMaskWrapper::ANG_X,
+            JointAxesMaskWrapper::ANG_Y,
+            JointAxesMaskWrapper::ANG_Z,
+        ),
+        // urdf's default axis
+        _ => return urdf_rs::Vec3([1.0, 0.0, 0.0]),
+    };
+    let frame_rotation = joint.local_frame2.unwrap_or_default().rotation.as_dquat();
+    let axis = coordinates.pose_basis().inverse() * frame_rotation * joint_frame_axis;
+    urdf_rs::Vec3(axis.to_array())
+}
+
+/// the `local_frame1` and `local_frame2` of a urdf joint.
+///
+/// Joints only free whole axes of their frames, so both frames are turned to line the joint's x axis up with its
+/// urdf axis, whatever direction that is. Revolute, continuous and prismatic joints then free x, and planar joints
+/// free the plane normal to it.
+pub fn joint_frames(joint: &Joint, coordinates: UrdfCoordinates) -> (Transform, Transform) {
+    let origin = UrdfTransform::from(joint.origin.clone()).into_transform(coordinates);
+    let axis = DVec3::from_array(joint.axis.xyz.0)
+        .try_normalize()
+        .unwrap_or(DVec3::X);
+    let alignment = Transform::from_rotation(
+        DQuat::from_rotation_arc(DVec3::X, coordinates.pose_basis() * axis).as_quat(),
+    );
+    (origin * alignment, alignment)
+}
+
+/// the urdf joint origin of a [`JointFlag`], undoing the alignment [`joint_frames`] adds.
+pub fn joint_origin(joint: &JointFlag) -> Transform {
+    let alignment = joint.local_frame2.unwrap_or_default();
+    joint.local_frame1 * Transform::from_rotation(alignment.rotation.inverse())
+}
+
 #[derive(From)]
 pub struct LinkWrapper(Link);
 
@@ -238,8 +1131,9 @@ impl From<&LinkWrapper> for LinkFlag {
             .first()
             .unwrap_or(&Visual::default())
             .to_owned();
+        // visuals are spawned as child entities with their own origins, so this only carries the first visual's
+        // offset for anything still reading it off of the link.
         Self {
-            //FIXME: implement this properly to account for urdfs with multiple visual elements
             geom_offset: Vec3::from_array([
                 visual.origin.xyz[0] as f32,
                 visual.origin.xyz[1] as f32,
@@ -250,43 +1144,159 @@ impl From<&LinkWrapper> for LinkFlag {
 }
 
 #[derive(From)]
-pub struct UrdfTransform(Pose);
+pub struct InertialWrapper(Inertial);
 
-impl From<UrdfTransform> for Transform {
-    fn from(value: UrdfTransform) -> Self {
-        // based on this explanation
-        //https://towardsdatascience.com/change-of-basis-3909ef4bed43
-        let urdf_cord_flip = Matrix3::new(1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0);
-        // based on this explanation
-        //https://stackoverflow.com/questions/31191752/right-handed-euler-angles-xyz-to-left-handed-euler-angles-xyz
-        let urdf_rotation_flip = Matrix3::new(-1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0);
-        let pos = value.0;
-
-        let compliant_trans =
-            urdf_cord_flip * Vector3::new(pos.xyz.0[0], pos.xyz.0[1], pos.xyz.0[2]);
-        let compliant_rot =
-            urdf_rotation_flip * Vector3::new(pos.rpy.0[0], pos.rpy.0[1], pos.rpy.0[2]);
+impl From<&InertialWrapper> for MassFlag {
+    fn from(value: &InertialWrapper) -> Self {
+        Self {
+            mass: value.0.mass.value as f32,
+        }
+    }
+}
+
+impl From<&InertialWrapper> for InertialFlag {
+    fn from(value: &InertialWrapper) -> Self {
+        let inertia = &value.0.inertia;
+        Self {
+            center_of_mass: UrdfTransform::from(value.0.origin.clone()).into(),
+            ixx: inertia.ixx as f32,
+            ixy: inertia.ixy as f32,
+            ixz: inertia.ixz as f32,
+            iyy: inertia.iyy as f32,
+            iyz: inertia.iyz as f32,
+            izz: inertia.izz as f32,
+        }
+    }
+}
 
+/// the components a link's `<inertial>` is split into, for converting back to urdf.
+pub struct InertialFlagsWrapper {
+    /// see [`MassFlag`]
+    pub mass: f32,
+    pub inertial: InertialFlag,
+    pub coordinates: UrdfCoordinates,
+}
+
+impl From<&InertialFlagsWrapper> for Inertial {
+    fn from(value: &InertialFlagsWrapper) -> Self {
+        let inertial = &value.inertial;
         Self {
-            translation: Vec3::new(
-                compliant_trans.x as f32,
-                compliant_trans.y as f32,
-                compliant_trans.z as f32,
-            ),
-            rotation: Quat::from_euler(
-                EulerRot::XYZ,
-                compliant_rot.x as f32,
-                compliant_rot.y as f32,
-                compliant_rot.z as f32,
-            ),
+            origin: UrdfTransform::from_transform(&inertial.center_of_mass, value.coordinates)
+                .into(),
+            mass: Mass {
+                value: value.mass.into(),
+            },
+            inertia: Inertia {
+                ixx: inertial.ixx.into(),
+                ixy: inertial.ixy.into(),
+                ixz: inertial.ixz.into(),
+                iyy: inertial.iyy.into(),
+                iyz: inertial.iyz.into(),
+                izz: inertial.izz.into(),
+            },
+        }
+    }
+}
+
+#[derive(From)]
+pub struct UrdfTransform(Pose);
+
+/// rotation from urdf's z-up axes to bevy's y-up axes.
+///
+/// This is a proper rotation(a quarter turn around x), unlike swapping y and z, so handedness and with it the
+/// direction of every rotation is preserved.
+pub fn urdf_to_bevy_basis() -> DQuat {
+    DQuat::from_rotation_x(-FRAC_PI_2)
+}
+
+impl UrdfTransform {
+    /// converts the pose to a [`Transform`], changing its basis as `coordinates` asks for.
+    pub fn into_transform(self, coordinates: UrdfCoordinates) -> Transform {
+        let pose = self.0;
+        let basis = coordinates.pose_basis();
+        // urdf's rpy rotates around the fixed x, then y, then z axes, which is `Rz(yaw) * Ry(pitch) * Rx(roll)`.
+        let urdf_rotation = DQuat::from_euler(EulerRot::ZYX, pose.rpy[2], pose.rpy[1], pose.rpy[0]);
+
+        Transform {
+            translation: (basis * DVec3::from_array(pose.xyz.0)).as_vec3(),
+            // a change of basis applies to the whole rotation, not to each euler angle on its own.
+            rotation: (basis * urdf_rotation * basis.inverse()).as_quat(),
             ..default()
         }
     }
+
+    /// inverse of [`Self::into_transform`]. Scale is ignored.
+    pub fn from_transform(transform: &Transform, coordinates: UrdfCoordinates) -> Self {
+        let basis = coordinates.pose_basis();
+        let translation = basis.inverse() * transform.translation.as_dvec3();
+        let urdf_rotation = basis.inverse() * transform.rotation.as_dquat() * basis;
+        let (yaw, pitch, roll) = urdf_rotation.to_euler(EulerRot::ZYX);
+
+        Self(Pose {
+            xyz: urdf_rs::Vec3(translation.to_array()),
+            rpy: urdf_rs::Vec3([roll, pitch, yaw]),
+        })
+    }
+
+    /// like [`Self::into_transform`], for entities holding `<visual>`/`<collision>` geometry. Meshes and primitives
+    /// keep urdf's axes, so the change of basis is applied to the geometry as well.
+    pub fn into_geometry_transform(self, coordinates: UrdfCoordinates) -> Transform {
+        let mut transform = self.into_transform(coordinates);
+        transform.rotation *= coordinates.pose_basis().as_quat();
+        transform
+    }
+
+    /// inverse of [`Self::into_geometry_transform`]. Scale is ignored.
+    pub fn from_geometry_transform(transform: &Transform, coordinates: UrdfCoordinates) -> Self {
+        let mut pose_transform = *transform;
+        pose_transform.rotation *= coordinates.pose_basis().inverse().as_quat();
+        Self::from_transform(&pose_transform, coordinates)
+    }
+}
+
+impl From<UrdfTransform> for Transform {
+    fn from(value: UrdfTransform) -> Self {
+        value.into_transform(UrdfCoordinates::default())
+    }
+}
+
+impl From<&Transform> for UrdfTransform {
+    fn from(value: &Transform) -> Self {
+        Self::from_transform(value, UrdfCoordinates::default())
+    }
+}
+
+impl From<UrdfTransform> for Pose {
+    fn from(value: UrdfTransform) -> Self {
+        value.0
+    }
 }
 
 #[derive(From)]
 pub struct JointWrapper(Joint);
 
+/// motor settings for a urdf joint, from its `<dynamics>` and `<limit>`.
+///
+/// * damping is `<dynamics damping>`.
+/// * max force is `<limit effort>`. Joints without an effort limit aren't actuated, so their motors only hold
+///   back against `<dynamics friction>`, the way dry friction would. That leaves a joint with friction but no
+///   effort limit(e.g. a continuous wheel) only as strong as its friction, give it an effort limit to drive it.
+///
+/// Anything the urdf leaves out(or at 0) comes from `fallbacks`.
+pub fn joint_motor(joint: &urdf_rs::Joint, fallbacks: &JointMotorFallbacks) -> JointMotorWrapper {
+    let positive = |n: f64| Some(n as f32).filter(|n| *n > 0.0);
+    let dynamics = joint.dynamics.as_ref();
+    let damping = dynamics.and_then(|dynamics| positive(dynamics.damping));
+    let friction = dynamics.and_then(|dynamics| positive(dynamics.friction));
+    JointMotorWrapper {
+        damping: damping.unwrap_or(fallbacks.damping),
+        max_force: positive(joint.limit.effort)
+            .or(friction)
+            .unwrap_or(fallbacks.max_force),
+        ..default()
+    }
+}
+
 //FIXME: get rid of defaults as needed
 impl From<&JointWrapper> for JointFlag {
     fn from(value: &JointWrapper) -> Self {
@@ -294,22 +1304,44 @@ impl From<&JointWrapper> for JointFlag {
         //let axis = value.0.axis.xyz.0.clone();
 
         //let new_axis = JointAxesMaskWrapper
-        let motor_settings = JointMotorWrapper {
-            max_force: f32::MAX,
-            //FIXME: I pulled this number from rapier joint damping. This should be generated based on something like air-resistance at some point to be
-            //accurate.
-            damping: 20.0,
-            ..default()
+        let motor_settings = joint_motor(&value.0, &JointMotorFallbacks::default());
+
+        let default_locked_axes = JointAxesMaskWrapper::LOCKED_FIXED_AXES;
+        // the joint's frames are aligned so its urdf axis is x, see `joint_frames`.
+        let free_axes = match value.0.joint_type {
+            JointType::Fixed => JointAxesMaskWrapper::empty(),
+            JointType::Floating => default_locked_axes,
+            // prismatic joints slide along their axis.
+            JointType::Prismatic => JointAxesMaskWrapper::LIN_X,
+            // planar joints slide across the plane their axis is the normal of, and turn around it.
+            JointType::Planar => {
+                JointAxesMaskWrapper::LIN_Y
+                    | JointAxesMaskWrapper::LIN_Z
+                    | JointAxesMaskWrapper::ANG_X
+            }
+            JointType::Revolute | JointType::Continuous => JointAxesMaskWrapper::ANG_X,
+            JointType::Spherical => {
+                JointAxesMaskWrapper::ANG_X
+                    | JointAxesMaskWrapper::ANG_Y
+                    | JointAxesMaskWrapper::ANG_Z
+            }
+        };
+        let locked_axes = default_locked_axes.difference(free_axes);
+        let (local_frame1, local_frame2) = joint_frames(&value.0, UrdfCoordinates::default());
+
+        // continuous joints have no stops, and urdf-rs fills their missing `<limit>` in with 0..0
+        // (https://github.com/openrr/urdf-rs/issues/99), so only revolute and prismatic joints are limited.
+        let limit_axes = match value.0.joint_type {
+            JointType::Revolute | JointType::Prismatic => free_axes,
+            _ => JointAxesMaskWrapper::empty(),
         };
 
         Self {
             parent_name: Some(value.0.parent.link.clone()),
             parent_id: None,
             limit: JointLimitWrapper {
-                //FIXME: Workaround for urdfs defaulting to un-movable joints
-                //https://github.com/openrr/urdf-rs/issues/99
-                lower: -999999999999999.0, //f64::MIN, //value.0.limit.lower,
-                upper: 999999999999999.0,  //f64::MAX, //value.0.limit.upper,
+                lower: value.0.limit.lower,
+                upper: value.0.limit.upper,
                 effort: value.0.limit.effort,
                 velocity: value.0.limit.velocity,
             },
@@ -322,46 +1354,15 @@ impl From<&JointWrapper> for JointFlag {
                     None => Dynamics::default(),
                 }
             },
-            local_frame1: UrdfTransform::from(value.0.origin.clone()).into(),
-            local_frame2: None,
-            locked_axes: {
-                //clamp axis to between 0-1 for simplicity and for bitmask flipping
-                let default_locked_axes = JointAxesMaskWrapper::LOCKED_FIXED_AXES;
-                if value.0.joint_type != urdf_rs::JointType::Fixed {
-                    let unit_axis = value
-                        .0
-                        .axis
-                        .xyz
-                        .0
-                        .map(|n| n.abs().clamp(0.0, 1.0))
-                        .map(|n| n as u8);
-
-                    //println!("unit axis is !!! {:#?}", unit_axis);
-                    let mut x = default_locked_axes.bits();
-                    x ^= unit_axis[0] << 3;
-                    x ^= unit_axis[1] << 4;
-                    x ^= unit_axis[2] << 5;
-                    JointAxesMaskWrapper::from_bits(x).unwrap()
-                } else {
-                    default_locked_axes
-                }
-
-                //FIXME: Replace with proper "axis-alignment" metod for converting from urdf -> bevy
-                //JointAxesMaskWrapper::LOCKED_FIXED_AXES.difference(JointAxesMaskWrapper::ANG_Y)
-            },
-            limit_axes: JointAxesMaskWrapper::empty(),
+            local_frame1,
+            local_frame2: Some(local_frame2),
+            locked_axes,
+            limit_axes,
             motor_axes: JointAxesMaskWrapper::all(),
             coupled_axes: JointAxesMaskWrapper::empty(),
             contacts_enabled: true,
             enabled: true,
-            motors: [
-                motor_settings.clone(),
-                motor_settings.clone(),
-                motor_settings.clone(),
-                motor_settings.clone(),
-                motor_settings.clone(),
-                motor_settings.clone(),
-            ],
+            motors: std::array::from_fn(|_| motor_settings.clone()),
         }
     }
 }
@@ -0,0 +1,186 @@
+//! checks that links collide with their `<collision>` geometry, and with their `<visual>` geometry when asked to
+//! and the urdf leaves collisions out.
+
+mod common;
+
+use bevy_core::Name;
+use bevy_ecs::{prelude::*, query::QueryFilter};
+use bevy_hierarchy::Parent;
+use bevy_serialization_extras::prelude::{
+    colliders::ColliderFlag,
+    mesh::{GeometryFlag, MeshPrimitive},
+};
+use bevy_serialization_urdf::{
+    components::{LinkCollision, LinkVisual, UrdfCoordinates},
+    loaders::urdf_loader::load_urdf,
+    resources::UrdfSpawnSettings,
+    wrappers::UrdfTransform,
+};
+use bevy_transform::prelude::Transform;
+use common::{entity_named, round_trip, spawn};
+use urdf_rs::Pose;
+
+/// the kind of `primitive` and its dimensions.
+fn shape(primitive: &MeshPrimitive) -> (&'static str, Vec<f32>) {
+    match *primitive {
+        MeshPrimitive::Cuboid { size } => ("box", size.to_vec()),
+        MeshPrimitive::Cylinder { radius, length } => ("cylinder", vec![radius, length]),
+        MeshPrimitive::Capsule { radius, length } => ("capsule", vec![radius, length]),
+        MeshPrimitive::Sphere { radius } => ("sphere", vec![radius]),
+    }
+}
+
+/// (name, parent, shape, transform) of every collider in `world` matching `F`.
+#[allow(clippy::type_complexity)]
+fn colliders<F: QueryFilter>(
+    world: &mut World,
+) -> Vec<(Option<String>, Entity, (&'static str, Vec<f32>), Transform)> {
+    world
+        .query_filtered::<(
+            Option<&Name>,
+            &Parent,
+            &GeometryFlag,
+            &Transform,
+        ), (With<ColliderFlag>, F)>()
+        .iter(world)
+        .map(|(name, parent, geometry, transform)| {
+            (
+                name.map(|name| name.to_string()),
+                parent.get(),
+                shape(&geometry.primitive),
+                *transform,
+            )
+        })
+        .collect()
+}
+
+fn expected_transform(xyz: [f64; 3]) -> Transform {
+    UrdfTransform::from(Pose {
+        xyz: urdf_rs::Vec3(xyz),
+        rpy: urdf_rs::Vec3([0.0; 3]),
+    })
+    .into_geometry_transform(UrdfCoordinates::default())
+}
+
+#[test]
+fn collision_primitives_are_spawned_as_colliders() {
+    let urdf = load_urdf(
+        br#"<robot name="shapes">
+            <link name="base">
+                <visual>
+                    <geometry><box size="5 5 5"/></geometry>
+                </visual>
+                <collision name="box">
+                    <origin xyz="1 0 0" rpy="0 0 0"/>
+                    <geometry><box size="0.1 0.2 0.3"/></geometry>
+                </collision>
+                <collision name="cylinder">
+                    <origin xyz="0 1 0" rpy="0 0 0"/>
+                    <geometry><cylinder radius="0.1" length="0.5"/></geometry>
+                </collision>
+                <collision name="sphere">
+                    <origin xyz="0 0 1" rpy="0 0 0"/>
+                    <geometry><sphere radius="0.25"/></geometry>
+                </collision>
+                <collision name="capsule">
+                    <origin xyz="1 1 0" rpy="0 0 0"/>
+                    <geometry><capsule radius="0.05" length="0.4"/></geometry>
+                </collision>
+            </link>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(
+        &urdf,
+        UrdfSpawnSettings {
+            // links with `<collision>`s never collide with their visuals.
+            colliders_from_visuals: true,
+            ..Default::default()
+        },
+    );
+    let base = entity_named(&mut world, "base");
+
+    let mut collisions = colliders::<With<LinkCollision>>(&mut world);
+    collisions.sort_by_key(|(name, ..)| name.clone());
+    assert_eq!(collisions.len(), 4, "{collisions:?}");
+    for (name, parent, _, _) in &collisions {
+        assert_eq!(*parent, base, "{name:?} should be a child of its link");
+    }
+    // sorted by name, which is also each collision's kind of primitive.
+    let expected = [
+        ("box", vec![0.1, 0.2, 0.3], [1.0, 0.0, 0.0]),
+        ("capsule", vec![0.05, 0.4], [1.0, 1.0, 0.0]),
+        ("cylinder", vec![0.1, 0.5], [0.0, 1.0, 0.0]),
+        ("sphere", vec![0.25], [0.0, 0.0, 1.0]),
+    ];
+    for ((name, _, shape, transform), (expected_name, dimensions, xyz)) in
+        collisions.iter().zip(expected)
+    {
+        assert_eq!(name.as_deref(), Some(expected_name));
+        assert_eq!(*shape, (expected_name, dimensions), "{expected_name}");
+        let expected_transform = expected_transform(xyz);
+        assert!(
+            transform
+                .translation
+                .abs_diff_eq(expected_transform.translation, 1e-6)
+                && transform
+                    .rotation
+                    .abs_diff_eq(expected_transform.rotation, 1e-6),
+            "{expected_name}: expected {expected_transform:?}, got {transform:?}"
+        );
+    }
+    assert!(
+        colliders::<Without<LinkCollision>>(&mut world).is_empty(),
+        "links with collisions shouldn't get colliders from their visuals"
+    );
+}
+
+#[test]
+fn links_without_collisions_collide_with_their_visuals_when_asked_to() {
+    let urdf = load_urdf(
+        br#"<robot name="visual_only">
+            <link name="base">
+                <visual>
+                    <origin xyz="0 0 0.5" rpy="0 0 0"/>
+                    <geometry><sphere radius="0.25"/></geometry>
+                </visual>
+            </link>
+        </robot>"#,
+    )
+    .unwrap();
+
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    assert!(
+        colliders::<()>(&mut world).is_empty(),
+        "visuals shouldn't collide unless asked to"
+    );
+
+    let mut world = spawn(
+        &urdf,
+        UrdfSpawnSettings {
+            colliders_from_visuals: true,
+            ..Default::default()
+        },
+    );
+    let base = entity_named(&mut world, "base");
+    let stand_ins = colliders::<(Without<LinkCollision>, Without<LinkVisual>)>(&mut world);
+    assert_eq!(stand_ins.len(), 1, "{stand_ins:?}");
+    let (_, parent, shape, transform) = &stand_ins[0];
+    assert_eq!(*parent, base);
+    assert_eq!(*shape, ("sphere", vec![0.25]));
+    assert!(transform
+        .translation
+        .abs_diff_eq(expected_transform([0.0, 0.0, 0.5]).translation, 1e-6));
+
+    let exported = round_trip(
+        &urdf,
+        UrdfSpawnSettings {
+            colliders_from_visuals: true,
+            ..Default::default()
+        },
+    );
+    assert!(
+        exported.robot.links[0].collision.is_empty(),
+        "colliders from visuals shouldn't be saved back out"
+    );
+}
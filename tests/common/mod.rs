@@ -0,0 +1,77 @@
+//! helpers shared by the integration tests. Each test file only uses some of them.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use bevy_core::Name;
+use bevy_ecs::{prelude::*, system::SystemState, world::CommandQueue};
+use bevy_serialization_extras::prelude::{AssetSpawnRequest, FromStructure, IntoHashMap};
+use bevy_serialization_urdf::{
+    loaders::urdf_loader::{load_urdf, Urdf},
+    resources::UrdfSpawnSettings,
+    wrappers::LinkQuery,
+};
+use bevy_transform::prelude::Transform;
+use glam::{DQuat, EulerRot};
+use urdf_rs::Pose;
+
+/// positions and angles go through f32 on the way, so they won't come back exact.
+pub const TOLERANCE: f64 = 1e-3;
+
+/// loads the urdf at `path`, relative to `assets/`.
+pub fn load(path: &str) -> Urdf {
+    let bytes = std::fs::read(format!("{}/assets/{}", env!("CARGO_MANIFEST_DIR"), path))
+        .unwrap_or_else(|err| panic!("{path}: {err}"));
+    load_urdf(&bytes).unwrap_or_else(|err| panic!("{path}: {err}"))
+}
+
+/// spawns `urdf` into an empty world with `settings`.
+pub fn spawn(urdf: &Urdf, settings: UrdfSpawnSettings) -> World {
+    let mut world = World::new();
+    world.insert_resource(settings);
+    spawn_into(&mut world, urdf, Transform::IDENTITY);
+    world
+}
+
+/// spawns another copy of `urdf` into `world`, with its root link at `position`.
+pub fn spawn_into(world: &mut World, urdf: &Urdf, position: Transform) {
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, world);
+    Urdf::into_entities(
+        &mut commands,
+        urdf.clone(),
+        AssetSpawnRequest {
+            position,
+            ..Default::default()
+        },
+    );
+    queue.apply(world);
+}
+
+/// reads every robot in `world` back out, by structure name.
+pub fn read_back(world: &mut World) -> HashMap<String, Urdf> {
+    let mut links = SystemState::<Query<LinkQuery>>::new(world);
+    Urdf::into_hashmap(links.get(world))
+}
+
+/// spawns `urdf` into an empty world with `settings`, then reads it back out.
+pub fn round_trip(urdf: &Urdf, settings: UrdfSpawnSettings) -> Urdf {
+    read_back(&mut spawn(urdf, settings))
+        .remove(&urdf.robot.name)
+        .expect("robot should be read back out under its own name")
+}
+
+/// the entity named `name`, e.g: a link.
+pub fn entity_named(world: &mut World, name: &str) -> Entity {
+    world
+        .query::<(Entity, &Name)>()
+        .iter(world)
+        .find(|(_, entity_name)| entity_name.as_str() == name)
+        .map(|(e, _)| e)
+        .unwrap_or_else(|| panic!("nothing named {name}"))
+}
+
+/// urdf rpy is a fixed axis roll, then pitch, then yaw.
+pub fn urdf_rotation(pose: &Pose) -> DQuat {
+    DQuat::from_euler(EulerRot::ZYX, pose.rpy[2], pose.rpy[1], pose.rpy[0])
+}
@@ -0,0 +1,102 @@
+//! checks that links are handed to rapier with their urdf `<inertial>`, rather than one worked out from their
+//! colliders.
+
+mod common;
+
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::Children;
+use bevy_rapier3d::prelude::{AdditionalMassProperties, ColliderMassProperties};
+use bevy_serialization_urdf::{
+    loaders::urdf_loader::load_urdf, resources::UrdfSpawnSettings, systems::apply_inertials,
+    wrappers::urdf_to_bevy_basis,
+};
+use common::{entity_named, spawn, urdf_rotation};
+use glam::{DMat3, DVec3, Mat3};
+use urdf_rs::Pose;
+
+#[test]
+fn links_are_given_their_urdf_mass_properties() {
+    let urdf = load_urdf(
+        br#"<robot name="weighted">
+            <link name="base">
+                <inertial>
+                    <origin xyz="0.1 0.2 0.3" rpy="0.4 0 0.2"/>
+                    <mass value="2"/>
+                    <inertia ixx="1" ixy="0.1" ixz="0" iyy="2" iyz="0" izz="3"/>
+                </inertial>
+                <collision>
+                    <geometry><box size="1 1 1"/></geometry>
+                </collision>
+            </link>
+            <link name="frame">
+                <collision>
+                    <geometry><sphere radius="0.1"/></geometry>
+                </collision>
+            </link>
+            <joint name="base_to_frame" type="fixed">
+                <parent link="base"/>
+                <child link="frame"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    let mut schedule = Schedule::default();
+    schedule.add_systems(apply_inertials);
+    schedule.run(&mut world);
+
+    let base = entity_named(&mut world, "base");
+    let Some(AdditionalMassProperties::MassProperties(mass_properties)) =
+        world.get::<AdditionalMassProperties>(base).copied()
+    else {
+        panic!("base should have full mass properties");
+    };
+    assert_eq!(mass_properties.mass, 2.0);
+
+    // the urdf's center of mass frame, in the spawned link's axes.
+    let origin = Pose {
+        xyz: urdf_rs::Vec3([0.1, 0.2, 0.3]),
+        rpy: urdf_rs::Vec3([0.4, 0.0, 0.2]),
+    };
+    let basis = urdf_to_bevy_basis();
+    let center_of_mass = basis * DVec3::from_array(origin.xyz.0);
+    assert!(
+        mass_properties
+            .local_center_of_mass
+            .as_dvec3()
+            .abs_diff_eq(center_of_mass, 1e-6),
+        "expected center of mass {center_of_mass}, got {}",
+        mass_properties.local_center_of_mass
+    );
+    let axes = DMat3::from_quat(basis * urdf_rotation(&origin));
+    let tensor = DMat3::from_cols(
+        DVec3::new(1.0, 0.1, 0.0),
+        DVec3::new(0.1, 2.0, 0.0),
+        DVec3::new(0.0, 0.0, 3.0),
+    );
+    let expected = axes * tensor * axes.transpose();
+    let principal_axes = Mat3::from_quat(mass_properties.principal_inertia_local_frame);
+    let actual = (principal_axes
+        * Mat3::from_diagonal(mass_properties.principal_inertia)
+        * principal_axes.transpose())
+    .as_dmat3();
+    assert!(
+        actual.abs_diff_eq(expected, 1e-5),
+        "expected inertia {expected}, got {actual}"
+    );
+
+    // the collider's mass would come on top of the urdf's.
+    let collider = world.get::<Children>(base).unwrap()[0];
+    assert_eq!(
+        world.get::<ColliderMassProperties>(collider),
+        Some(&ColliderMassProperties::Density(0.0))
+    );
+
+    // links without a mass are left to their colliders.
+    let frame = entity_named(&mut world, "frame");
+    assert!(world.get::<AdditionalMassProperties>(frame).is_none());
+    let frame_collider = world.get::<Children>(frame).unwrap()[0];
+    assert!(world
+        .get::<ColliderMassProperties>(frame_collider)
+        .is_none());
+}
@@ -0,0 +1,70 @@
+//! checks which of a joint's `<dynamics>` and `<limit>` its motor settings come from.
+
+use bevy_serialization_urdf::{
+    loaders::urdf_loader::load_urdf, resources::JointMotorFallbacks, wrappers::joint_motor,
+};
+
+#[test]
+fn motors_come_from_dynamics_and_limits_then_fallbacks() {
+    let urdf = load_urdf(
+        br#"<robot name="motors">
+            <link name="base"/>
+            <link name="actuated"/>
+            <link name="unactuated"/>
+            <link name="bare"/>
+            <joint name="actuated" type="revolute">
+                <parent link="base"/>
+                <child link="actuated"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="-1" upper="1" effort="10" velocity="1"/>
+                <dynamics damping="0.5" friction="2"/>
+            </joint>
+            <joint name="unactuated" type="continuous">
+                <parent link="base"/>
+                <child link="unactuated"/>
+                <axis xyz="0 0 1"/>
+                <dynamics damping="0" friction="2"/>
+            </joint>
+            <joint name="bare" type="continuous">
+                <parent link="base"/>
+                <child link="bare"/>
+                <axis xyz="0 0 1"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let fallbacks = JointMotorFallbacks {
+        damping: 20.0,
+        max_force: 100.0,
+    };
+    let motor = |name: &str| {
+        let joint = urdf
+            .robot
+            .joints
+            .iter()
+            .find(|joint| joint.name == name)
+            .unwrap();
+        joint_motor(joint, &fallbacks)
+    };
+
+    let actuated = motor("actuated");
+    assert_eq!(actuated.damping, 0.5);
+    assert_eq!(
+        actuated.max_force, 10.0,
+        "effort should come before friction"
+    );
+
+    let unactuated = motor("unactuated");
+    assert_eq!(
+        unactuated.damping, 20.0,
+        "0 damping should be left to the fallback"
+    );
+    assert_eq!(
+        unactuated.max_force, 2.0,
+        "friction should hold back joints without an effort limit"
+    );
+
+    let bare = motor("bare");
+    assert_eq!(bare.damping, 20.0);
+    assert_eq!(bare.max_force, 100.0);
+}
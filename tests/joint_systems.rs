@@ -0,0 +1,233 @@
+//! checks that the systems in [`UrdfJointSystems`](bevy_serialization_urdf::plugin::UrdfJointSystems) keep joint
+//! motors in line with their urdf.
+
+mod common;
+
+use bevy_ecs::prelude::*;
+use bevy_serialization_extras::prelude::link::JointFlag;
+use bevy_serialization_urdf::{
+    loaders::urdf_loader::load_urdf,
+    resources::UrdfSpawnSettings,
+    systems::{apply_mimic_joints, apply_safety_controllers, clamp_motors_to_joint_limits},
+    wrappers::{driven_motor, joint_position},
+};
+use bevy_transform::prelude::{GlobalTransform, Transform};
+use common::{entity_named, spawn};
+use glam::Quat;
+
+/// the [`JointFlag`] of `link` and the index of its driven motor.
+fn joint_mut<'w>(world: &'w mut World, link: Entity) -> (Mut<'w, JointFlag>, usize) {
+    let joint = world.get_mut::<JointFlag>(link).unwrap();
+    let i = driven_motor(&joint).expect("joint should have a single free axis");
+    (joint, i)
+}
+
+fn run<M>(world: &mut World, systems: impl IntoSystemConfigs<M>) {
+    let mut schedule = Schedule::default();
+    schedule.add_systems(systems);
+    schedule.run(world);
+}
+
+#[test]
+fn mimic_joints_follow_their_leader() {
+    let urdf = load_urdf(
+        br#"<robot name="gripper">
+            <link name="palm"/>
+            <link name="left_finger"/>
+            <link name="right_finger"/>
+            <joint name="left_finger_joint" type="revolute">
+                <parent link="palm"/>
+                <child link="left_finger"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="-1" upper="1" effort="0" velocity="0"/>
+            </joint>
+            <joint name="right_finger_joint" type="revolute">
+                <parent link="palm"/>
+                <child link="right_finger"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="-1" upper="1" effort="0" velocity="0"/>
+                <mimic joint="left_finger_joint" multiplier="-1" offset="0.1"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    let leader = entity_named(&mut world, "left_finger");
+    let follower = entity_named(&mut world, "right_finger");
+    {
+        let (mut joint, i) = joint_mut(&mut world, leader);
+        joint.motors[i].target_pos = 0.3;
+        joint.motors[i].target_vel = 0.2;
+    }
+
+    run(&mut world, apply_mimic_joints);
+
+    let (joint, i) = joint_mut(&mut world, follower);
+    assert!(
+        (joint.motors[i].target_pos - -0.2).abs() < 1e-6,
+        "{}",
+        joint.motors[i].target_pos
+    );
+    assert!(
+        (joint.motors[i].target_vel - -0.2).abs() < 1e-6,
+        "{}",
+        joint.motors[i].target_vel
+    );
+}
+
+#[test]
+fn motors_are_clamped_to_effort_and_velocity_limits() {
+    let urdf = load_urdf(
+        br#"<robot name="arm">
+            <link name="base"/>
+            <link name="elbow"/>
+            <link name="wheel"/>
+            <joint name="elbow_joint" type="revolute">
+                <parent link="base"/>
+                <child link="elbow"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="-1" upper="1" effort="10" velocity="1"/>
+            </joint>
+            <joint name="wheel_joint" type="continuous">
+                <parent link="base"/>
+                <child link="wheel"/>
+                <axis xyz="0 1 0"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    let elbow = entity_named(&mut world, "elbow");
+    let wheel = entity_named(&mut world, "wheel");
+    for (e, target_vel) in [(elbow, 20.0), (wheel, -20.0)] {
+        let (mut joint, i) = joint_mut(&mut world, e);
+        joint.motors[i].target_vel = target_vel;
+        joint.motors[i].max_force = 1000.0;
+    }
+
+    run(&mut world, clamp_motors_to_joint_limits);
+
+    let (joint, i) = joint_mut(&mut world, elbow);
+    assert_eq!(joint.motors[i].target_vel, 1.0);
+    assert_eq!(joint.motors[i].max_force, 10.0);
+    // the wheel's limits are left out, so 0, which means unlimited.
+    let (joint, i) = joint_mut(&mut world, wheel);
+    assert_eq!(joint.motors[i].target_vel, -20.0);
+    assert_eq!(joint.motors[i].max_force, 1000.0);
+}
+
+#[test]
+fn motor_targets_are_kept_within_safety_controller_limits() {
+    let urdf = load_urdf(
+        br#"<robot name="arm">
+            <link name="base"/>
+            <link name="elbow"/>
+            <joint name="elbow_joint" type="revolute">
+                <parent link="base"/>
+                <child link="elbow"/>
+                <origin xyz="0 0 0.5" rpy="0 0 0"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="0" upper="0.5" effort="10" velocity="1"/>
+                <safety_controller soft_lower_limit="0.05" soft_upper_limit="0.45" k_position="15" k_velocity="10"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    let elbow = entity_named(&mut world, "elbow");
+    // turn the elbow 0.5 around its joint, past the soft upper limit.
+    let (frame1, frame2) = {
+        let joint = world.get::<JointFlag>(elbow).unwrap();
+        (joint.local_frame1, joint.local_frame2.unwrap_or_default())
+    };
+    let turned = frame1
+        * Transform::from_rotation(Quat::from_rotation_x(0.5))
+        * Transform::from_matrix(frame2.compute_matrix().inverse());
+    world
+        .entity_mut(elbow)
+        .insert(GlobalTransform::from(turned));
+    {
+        let (mut joint, i) = joint_mut(&mut world, elbow);
+        joint.motors[i].target_pos = 0.6;
+        joint.motors[i].target_vel = 5.0;
+        joint.motors[i].stiffness = 0.0;
+    }
+
+    run(&mut world, apply_safety_controllers);
+
+    // past the soft upper limit, the joint may only move back down.
+    let (joint, i) = joint_mut(&mut world, elbow);
+    assert!(
+        (joint.motors[i].target_vel - -15.0 * 0.05).abs() < 1e-4,
+        "{}",
+        joint.motors[i].target_vel
+    );
+    assert_eq!(
+        joint.motors[i].target_pos, 0.6,
+        "position targets of motors without stiffness should be left alone"
+    );
+
+    {
+        let (mut joint, i) = joint_mut(&mut world, elbow);
+        joint.motors[i].stiffness = 1.0;
+    }
+    run(&mut world, apply_safety_controllers);
+
+    let (joint, i) = joint_mut(&mut world, elbow);
+    assert!(
+        (joint.motors[i].target_pos - 0.45).abs() < 1e-6,
+        "{}",
+        joint.motors[i].target_pos
+    );
+}
+
+#[test]
+fn joint_positions_are_measured_from_their_links() {
+    let urdf = load_urdf(
+        br#"<robot name="arm">
+            <link name="base"/>
+            <link name="elbow"/>
+            <link name="slider"/>
+            <joint name="elbow_joint" type="revolute">
+                <parent link="base"/>
+                <child link="elbow"/>
+                <origin xyz="0 0 0.5" rpy="0 0.3 0"/>
+                <axis xyz="0 0.7071 0.7071"/>
+                <limit lower="-2" upper="2" effort="10" velocity="1"/>
+            </joint>
+            <joint name="slider_joint" type="prismatic">
+                <parent link="base"/>
+                <child link="slider"/>
+                <axis xyz="-1 0 0"/>
+                <limit lower="-1" upper="1" effort="10" velocity="1"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    let base = GlobalTransform::from(Transform::from_xyz(1.0, 2.0, 3.0));
+    for (link, moved) in [
+        (
+            "elbow",
+            Transform::from_rotation(Quat::from_rotation_x(-1.2)),
+        ),
+        ("slider", Transform::from_xyz(0.3, 0.0, 0.0)),
+    ] {
+        let e = entity_named(&mut world, link);
+        let joint = world.get::<JointFlag>(e).unwrap();
+        let frame2 = joint.local_frame2.unwrap_or_default();
+        let child = base.compute_transform()
+            * joint.local_frame1
+            * moved
+            * Transform::from_matrix(frame2.compute_matrix().inverse());
+        let position = joint_position(joint, &base, &GlobalTransform::from(child)).unwrap();
+        let expected = match link {
+            "elbow" => -1.2,
+            _ => 0.3,
+        };
+        assert!(
+            (position - expected).abs() < 1e-5,
+            "{link}: expected {expected}, got {position}"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! checks that links merged by [`UrdfSpawnSettings::merge_fixed_joints`] become one rigid body with their
+//! combined mass, and that physics gets that combined mass.
+
+mod common;
+
+use bevy_core::Name;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::Children;
+use bevy_rapier3d::prelude::{AdditionalMassProperties, ColliderMassProperties};
+use bevy_serialization_extras::prelude::{
+    link::JointFlag, mass::MassFlag, rigidbodies::RigidBodyFlag,
+};
+use bevy_serialization_urdf::{
+    components::{LinkInertial, MergedLink},
+    loaders::urdf_loader::load_urdf,
+    resources::UrdfSpawnSettings,
+    systems::apply_inertials,
+    wrappers::{combined_inertial, urdf_to_bevy_basis},
+};
+use bevy_transform::prelude::Transform;
+use common::{entity_named, spawn};
+use glam::{DMat3, DVec3, Mat3, Vec3};
+use urdf_rs::{Inertia, Inertial, Mass, Pose};
+
+const TOLERANCE: f64 = 1e-6;
+
+fn point_mass(mass: f64) -> Inertial {
+    Inertial {
+        origin: Pose::default(),
+        mass: Mass { value: mass },
+        inertia: Inertia::default(),
+    }
+}
+
+#[test]
+fn combined_inertial_moves_tensors_to_the_combined_center_of_mass() {
+    let left = point_mass(1.0);
+    let right = point_mass(3.0);
+    let combined = combined_inertial([
+        (
+            Transform::from_translation(Vec3::new(-1.0, 0.0, 0.0)),
+            &left,
+        ),
+        (
+            Transform::from_translation(Vec3::new(1.0, 0.0, 0.0)),
+            &right,
+        ),
+    ]);
+
+    assert!((combined.mass.value - 4.0).abs() <= TOLERANCE);
+    assert!((combined.origin.xyz[0] - 0.5).abs() <= TOLERANCE);
+    // 1kg at 1.5m and 3kg at 0.5m from the center of mass.
+    let expected = 1.0 * 1.5 * 1.5 + 3.0 * 0.5 * 0.5;
+    assert!(combined.inertia.ixx.abs() <= TOLERANCE);
+    assert!((combined.inertia.iyy - expected).abs() <= TOLERANCE);
+    assert!((combined.inertia.izz - expected).abs() <= TOLERANCE);
+}
+
+#[test]
+fn fixed_joint_subtrees_spawn_as_one_rigid_body() {
+    let urdf = load_urdf(
+        br#"<robot name="merged">
+            <link name="base"><inertial><mass value="2"/><inertia ixx="0" ixy="0" ixz="0" iyy="0" iyz="0" izz="0"/></inertial></link>
+            <link name="leg"><inertial><mass value="1"/><inertia ixx="0" ixy="0" ixz="0" iyy="0" iyz="0" izz="0"/></inertial></link>
+            <link name="foot"><inertial><mass value="0.5"/><inertia ixx="0" ixy="0" ixz="0" iyy="0" iyz="0" izz="0"/></inertial></link>
+            <link name="wheel"><inertial><mass value="0.25"/><inertia ixx="0" ixy="0" ixz="0" iyy="0" iyz="0" izz="0"/></inertial></link>
+            <joint name="base_to_leg" type="fixed">
+                <parent link="base"/>
+                <child link="leg"/>
+                <origin xyz="0 0 -0.5" rpy="0 0 0"/>
+            </joint>
+            <joint name="leg_to_foot" type="fixed">
+                <parent link="leg"/>
+                <child link="foot"/>
+                <origin xyz="0 0 -0.5" rpy="0 0 0"/>
+            </joint>
+            <joint name="foot_to_wheel" type="continuous">
+                <parent link="foot"/>
+                <child link="wheel"/>
+                <axis xyz="0 1 0"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+
+    let mut world = spawn(
+        &urdf,
+        UrdfSpawnSettings {
+            merge_fixed_joints: true,
+            ..Default::default()
+        },
+    );
+
+    let base = entity_named(&mut world, "base");
+    let mut links = world.query::<(
+        &Name,
+        Option<&RigidBodyFlag>,
+        Option<&MassFlag>,
+        Option<&JointFlag>,
+        Option<&MergedLink>,
+        Option<&LinkInertial>,
+    )>();
+    for (name, rigid_body, mass, joint, merged, own_inertial) in links.iter(&world) {
+        match name.as_str() {
+            "base" => {
+                assert!(rigid_body.is_some(), "base: should be a rigid body");
+                assert!(
+                    (mass.unwrap().mass - 3.5).abs() <= 1e-6,
+                    "base: should carry the merged mass"
+                );
+                assert!(
+                    (own_inertial.unwrap().mass - 2.0).abs() <= 1e-6,
+                    "base: should keep its own mass"
+                );
+            }
+            "leg" | "foot" => {
+                assert!(rigid_body.is_none(), "{name}: shouldn't be a rigid body");
+                assert!(mass.is_none(), "{name}: shouldn't have mass of its own");
+                assert!(joint.is_none(), "{name}: shouldn't have a joint");
+                assert_eq!(merged.map(|merged| merged.body), Some(base), "{name}: body");
+            }
+            "wheel" => {
+                assert!(rigid_body.is_some(), "wheel: should be a rigid body");
+                assert_eq!(
+                    joint.and_then(|joint| joint.parent_id),
+                    Some(base),
+                    "wheel: should be jointed to the body it's merged into"
+                );
+                assert!(merged.is_none(), "wheel: shouldn't be merged");
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn fixed_joints_onto_undeclared_links_are_not_merged() {
+    let urdf = load_urdf(
+        br#"<robot name="undeclared">
+            <link name="leg"/>
+            <joint name="base_to_leg" type="fixed">
+                <parent link="base"/>
+                <child link="leg"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+
+    let mut world = spawn(
+        &urdf,
+        UrdfSpawnSettings {
+            merge_fixed_joints: true,
+            ..Default::default()
+        },
+    );
+
+    let leg = entity_named(&mut world, "leg");
+    assert!(
+        world.get::<RigidBodyFlag>(leg).is_some(),
+        "leg: should be a rigid body of its own"
+    );
+    assert!(
+        world.get::<MergedLink>(leg).is_none(),
+        "leg: shouldn't be merged into a link that doesn't exist"
+    );
+}
+
+#[test]
+fn merged_bodies_hand_their_combined_inertia_to_rapier() {
+    let urdf = load_urdf(
+        br#"<robot name="merged">
+            <link name="base"><inertial><mass value="2"/><inertia ixx="0" ixy="0" ixz="0" iyy="0" iyz="0" izz="0"/></inertial></link>
+            <link name="leg">
+                <inertial><mass value="1"/><inertia ixx="0" ixy="0" ixz="0" iyy="0" iyz="0" izz="0"/></inertial>
+                <collision><geometry><box size="0.1 0.1 0.5"/></geometry></collision>
+            </link>
+            <joint name="base_to_leg" type="fixed">
+                <parent link="base"/>
+                <child link="leg"/>
+                <origin xyz="0 0 -0.5" rpy="0 0 0"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(
+        &urdf,
+        UrdfSpawnSettings {
+            merge_fixed_joints: true,
+            ..Default::default()
+        },
+    );
+    let mut schedule = Schedule::default();
+    schedule.add_systems(apply_inertials);
+    schedule.run(&mut world);
+
+    let base = entity_named(&mut world, "base");
+    let Some(AdditionalMassProperties::MassProperties(mass_properties)) =
+        world.get::<AdditionalMassProperties>(base).copied()
+    else {
+        panic!("base should have full mass properties");
+    };
+    assert!((mass_properties.mass - 3.0).abs() <= 1e-6);
+    // 2kg at the base and 1kg 0.5m below it.
+    let basis = urdf_to_bevy_basis();
+    let center_of_mass = basis * DVec3::new(0.0, 0.0, -0.5 / 3.0);
+    assert!(
+        mass_properties
+            .local_center_of_mass
+            .as_dvec3()
+            .abs_diff_eq(center_of_mass, 1e-6),
+        "expected center of mass {center_of_mass}, got {}",
+        mass_properties.local_center_of_mass
+    );
+    // 2kg 1/6m and 1kg 1/3m from the center of mass, both along urdf's z.
+    let axes = DMat3::from_quat(basis);
+    let expected = axes * DMat3::from_diagonal(DVec3::new(1.0, 1.0, 0.0) / 6.0) * axes.transpose();
+    let principal_axes = Mat3::from_quat(mass_properties.principal_inertia_local_frame);
+    let actual = (principal_axes
+        * Mat3::from_diagonal(mass_properties.principal_inertia)
+        * principal_axes.transpose())
+    .as_dmat3();
+    assert!(
+        actual.abs_diff_eq(expected, 1e-5),
+        "expected inertia {expected}, got {actual}"
+    );
+
+    // the merged link's collider is part of the body, so it mustn't add mass on top.
+    let leg = entity_named(&mut world, "leg");
+    let leg_collider = world.get::<Children>(leg).unwrap()[0];
+    assert_eq!(
+        world.get::<ColliderMassProperties>(leg_collider),
+        Some(&ColliderMassProperties::Density(0.0))
+    );
+}
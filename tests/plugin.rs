@@ -0,0 +1,102 @@
+//! checks that [`UrdfSerializationPlugin`]'s systems run where they take effect, with the whole plugin and rapier
+//! in an app.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_serialization_extras::prelude::link::JointFlag;
+use bevy_serialization_urdf::{
+    loaders::urdf_loader::load_urdf, plugin::UrdfSerializationPlugin, wrappers::driven_motor,
+};
+use common::{entity_named, spawn_into};
+
+/// a controller asking more of the wheel than its urdf allows.
+fn drive_wheel_too_fast(mut joints: Query<&mut JointFlag>) {
+    for mut joint in joints.iter_mut() {
+        if let Some(i) = driven_motor(&joint) {
+            joint.motors[i].target_vel = 20.0;
+            joint.motors[i].max_force = 1000.0;
+        }
+    }
+}
+
+#[test]
+fn physics_steps_with_constrained_motors() {
+    let urdf = load_urdf(
+        br#"<robot name="cart">
+            <link name="base"/>
+            <link name="wheel"/>
+            <joint name="wheel_joint" type="continuous">
+                <parent link="base"/>
+                <child link="wheel"/>
+                <axis xyz="0 1 0"/>
+                <limit effort="10" velocity="1"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        TransformPlugin,
+        HierarchyPlugin,
+        bevy::scene::ScenePlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<Image>()
+    .init_asset::<StandardMaterial>()
+    .add_plugins((
+        UrdfSerializationPlugin::default(),
+        RapierPhysicsPlugin::<NoUserData>::default(),
+    ))
+    .add_systems(Update, drive_wheel_too_fast);
+    spawn_into(app.world_mut(), &urdf, Transform::IDENTITY);
+
+    // stand in for the `RigidBodyFlag` and `JointFlag` to rapier sync of `SerializationPhysicsPlugin`, with the
+    // controller's unconstrained motor.
+    let base = entity_named(app.world_mut(), "base");
+    let wheel = entity_named(app.world_mut(), "wheel");
+    let (frame1, frame2) = {
+        let joint = app.world().get::<JointFlag>(wheel).unwrap();
+        (joint.local_frame1, joint.local_frame2.unwrap_or_default())
+    };
+    let joint = RevoluteJointBuilder::new(Vec3::X)
+        .local_anchor1(frame1.translation)
+        .local_anchor2(frame2.translation)
+        .motor_velocity(20.0, 0.0)
+        .motor_max_force(1000.0);
+    app.world_mut().entity_mut(base).insert(RigidBody::Dynamic);
+    app.world_mut()
+        .entity_mut(wheel)
+        .insert((RigidBody::Dynamic, ImpulseJoint::new(base, joint)));
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    // read from rapier's own joints, which are what physics steps with.
+    let handle = app
+        .world()
+        .get::<RapierImpulseJointHandle>(wheel)
+        .expect("the wheel's joint should be handed to rapier")
+        .0;
+    let context = app.world().resource::<RapierContext>();
+    let motor = *context
+        .impulse_joints
+        .get(handle)
+        .unwrap()
+        .data
+        .motor(JointAxis::AngX)
+        .unwrap();
+    assert_eq!(
+        motor.target_vel, 1.0,
+        "velocity should be clamped to the limit"
+    );
+    assert_eq!(
+        motor.max_force, 10.0,
+        "force should be clamped to the effort"
+    );
+}
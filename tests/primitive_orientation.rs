@@ -0,0 +1,104 @@
+//! checks that urdf primitives keep the axes the urdf spec gives them once spawned as [`GeometryFlag`]s.
+//!
+//! Per the spec, boxes are centered with their sides along the link's x, y and z, while cylinders and capsules
+//! run along the link's z. Bevy's cylinders and capsules run along y.
+
+use bevy_serialization_extras::prelude::{
+    mesh::{GeometryFile, GeometryFlag},
+    FileCheckPicker,
+};
+use bevy_serialization_urdf::{
+    components::UrdfCoordinates,
+    wrappers::{material_and_mesh::GeometryWrapper, urdf_to_bevy_basis, UrdfTransform},
+};
+use glam::{Mat3, Vec3};
+use urdf_rs::{Geometry, Pose};
+
+const TOLERANCE: f32 = 1e-5;
+
+fn geometry_flag(geometry: Geometry) -> GeometryFlag {
+    match FileCheckPicker::<GeometryFlag, GeometryFile>::from(&GeometryWrapper::from(geometry)) {
+        FileCheckPicker::PureComponent(flag) => flag,
+        FileCheckPicker::PathComponent(file) => {
+            panic!("expected a primitive, got {:?}", file.source)
+        }
+    }
+}
+
+/// where the primitive's own `axis` ends up in the link's(urdf) axes.
+fn in_link_axes(flag: &GeometryFlag, axis: Vec3) -> Vec3 {
+    Mat3::from_cols_array_2d(&flag.orientation_matrix.map(|col| col.to_array())) * axis
+}
+
+fn assert_same_axis(expected: Vec3, actual: Vec3, what: &str) {
+    assert!(
+        expected.abs_diff_eq(actual, TOLERANCE) || expected.abs_diff_eq(-actual, TOLERANCE),
+        "{what}: expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn cylinders_run_along_urdf_z() {
+    let flag = geometry_flag(Geometry::Cylinder {
+        radius: 0.1,
+        length: 1.0,
+    });
+    assert_same_axis(Vec3::Z, in_link_axes(&flag, Vec3::Y), "cylinder");
+}
+
+#[test]
+fn capsules_run_along_urdf_z() {
+    let flag = geometry_flag(Geometry::Capsule {
+        radius: 0.1,
+        length: 1.0,
+    });
+    assert_same_axis(Vec3::Z, in_link_axes(&flag, Vec3::Y), "capsule");
+}
+
+#[test]
+fn box_sides_follow_urdf_axes() {
+    let flag = geometry_flag(Geometry::Box {
+        size: urdf_rs::Vec3([1.0, 2.0, 3.0]),
+    });
+    for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+        assert_same_axis(axis, in_link_axes(&flag, axis), "box");
+    }
+}
+
+#[test]
+fn spheres_are_not_turned() {
+    let flag = geometry_flag(Geometry::Sphere { radius: 0.1 });
+    for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+        assert_same_axis(axis, in_link_axes(&flag, axis), "sphere");
+    }
+}
+
+#[test]
+fn long_axes_point_up_in_bevy_after_conversion() {
+    // a primitive at the link's origin, spawned converted to y-up, should stand along bevy's up like the link's z.
+    let geometry_transform =
+        UrdfTransform::from(Pose::default()).into_geometry_transform(UrdfCoordinates::ConvertToYUp);
+    let urdf_up = urdf_to_bevy_basis().as_quat() * Vec3::Z;
+    assert_same_axis(Vec3::Y, urdf_up, "urdf up");
+
+    for (what, geometry) in [
+        (
+            "cylinder",
+            Geometry::Cylinder {
+                radius: 0.1,
+                length: 1.0,
+            },
+        ),
+        (
+            "capsule",
+            Geometry::Capsule {
+                radius: 0.1,
+                length: 1.0,
+            },
+        ),
+    ] {
+        let flag = geometry_flag(geometry);
+        let long_axis = geometry_transform.rotation * in_link_axes(&flag, Vec3::Y);
+        assert_same_axis(Vec3::Y, long_axis, what);
+    }
+}
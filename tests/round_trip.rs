@@ -0,0 +1,732 @@
+//! checks that the urdfs under `assets/` survive being spawned with [`FromStructure`] and read back out with
+//! [`IntoHashMap`].
+
+mod common;
+
+use bevy_ecs::prelude::*;
+use bevy_serialization_urdf::{
+    components::{LinkCollision, LinkVisual, UrdfCoordinates},
+    loaders::urdf_loader::{load_urdf, Urdf},
+    resources::UrdfSpawnSettings,
+};
+use bevy_transform::prelude::Transform;
+use common::{
+    entity_named, load, read_back, round_trip, spawn, spawn_into, urdf_rotation, TOLERANCE,
+};
+use urdf_rs::{Geometry, JointType, Material, Pose, Robot};
+
+fn assert_close(expected: f64, actual: f64, what: &str) {
+    assert!(
+        (expected - actual).abs() <= TOLERANCE,
+        "{what}: expected {expected}, got {actual}"
+    );
+}
+
+/// for values too small for an absolute tolerance to mean anything, like the masses of small parts.
+fn assert_relatively_close(expected: f64, actual: f64, what: &str) {
+    assert!(
+        (expected - actual).abs() <= 1e-6 * expected.abs().max(actual.abs()),
+        "{what}: expected {expected}, got {actual}"
+    );
+}
+
+fn assert_pose_close(expected: &Pose, actual: &Pose, what: &str) {
+    for i in 0..3 {
+        assert_close(expected.xyz[i], actual.xyz[i], &format!("{what}: xyz[{i}]"));
+    }
+    // compare rotations rather than rpy, the same rotation has more than one rpy.
+    let angle = urdf_rotation(expected).angle_between(urdf_rotation(actual));
+    assert!(
+        angle <= TOLERANCE,
+        "{what}: expected rpy {:?}, got {:?}",
+        expected.rpy,
+        actual.rpy
+    );
+}
+
+fn assert_same_geometry(expected: &Geometry, actual: &Geometry, what: &str) {
+    match (expected, actual) {
+        (Geometry::Box { size: expected }, Geometry::Box { size: actual }) => {
+            for i in 0..3 {
+                assert_close(expected[i], actual[i], &format!("{what}: box size[{i}]"));
+            }
+        }
+        (
+            Geometry::Cylinder { radius, length },
+            Geometry::Cylinder {
+                radius: actual_radius,
+                length: actual_length,
+            },
+        )
+        | (
+            Geometry::Capsule { radius, length },
+            Geometry::Capsule {
+                radius: actual_radius,
+                length: actual_length,
+            },
+        ) => {
+            assert_close(*radius, *actual_radius, &format!("{what}: radius"));
+            assert_close(*length, *actual_length, &format!("{what}: length"));
+        }
+        (
+            Geometry::Sphere { radius },
+            Geometry::Sphere {
+                radius: actual_radius,
+            },
+        ) => {
+            assert_close(*radius, *actual_radius, &format!("{what}: radius"));
+        }
+        (
+            Geometry::Mesh { filename, scale },
+            Geometry::Mesh {
+                filename: actual_filename,
+                scale: actual_scale,
+            },
+        ) => {
+            assert_eq!(filename, actual_filename, "{what}: mesh filename");
+            let scale = scale.as_ref().map(|scale| scale.0).unwrap_or([1.0; 3]);
+            let actual_scale = actual_scale
+                .as_ref()
+                .map(|scale| scale.0)
+                .unwrap_or([1.0; 3]);
+            for i in 0..3 {
+                assert_relatively_close(
+                    scale[i],
+                    actual_scale[i],
+                    &format!("{what}: mesh scale[{i}]"),
+                );
+            }
+        }
+        _ => panic!("{what}: expected {expected:?}, got {actual:?}"),
+    }
+}
+
+/// the color of a visual's material, looking it up in the robot's material table if it's only referenced by name.
+fn resolved_rgba(robot: &Robot, material: Option<&Material>) -> Option<[f64; 4]> {
+    let material = material?;
+    let color = material.color.as_ref().or_else(|| {
+        robot
+            .materials
+            .iter()
+            .find(|declared| declared.name == material.name)?
+            .color
+            .as_ref()
+    })?;
+    Some(color.rgba.0)
+}
+
+/// the texture of a visual's material, looked up the same way as [`resolved_rgba`].
+fn resolved_texture(robot: &Robot, material: Option<&Material>) -> Option<String> {
+    let material = material?;
+    let texture = material.texture.as_ref().or_else(|| {
+        robot
+            .materials
+            .iter()
+            .find(|declared| declared.name == material.name)?
+            .texture
+            .as_ref()
+    })?;
+    Some(texture.filename.clone())
+}
+
+fn assert_round_trips(path: &str) {
+    assert_round_trips_with(path, UrdfSpawnSettings::default());
+}
+
+fn assert_round_trips_with(path: &str, settings: UrdfSpawnSettings) {
+    assert_urdf_round_trips(path, &load(path), settings);
+}
+
+/// `path` only names `original` in failure messages.
+fn assert_urdf_round_trips(path: &str, original: &Urdf, settings: UrdfSpawnSettings) {
+    assert_same_robot(path, original, &round_trip(original, settings));
+}
+
+/// `path` only names `original` in failure messages.
+fn assert_same_robot(path: &str, original: &Urdf, exported: &Urdf) {
+    let (original, exported) = (&original.robot, &exported.robot);
+
+    let mut link_names = original
+        .links
+        .iter()
+        .map(|link| &link.name)
+        .collect::<Vec<_>>();
+    let mut exported_link_names = exported
+        .links
+        .iter()
+        .map(|link| &link.name)
+        .collect::<Vec<_>>();
+    link_names.sort();
+    exported_link_names.sort();
+    assert_eq!(link_names, exported_link_names, "{path}: link names");
+
+    for link in &original.links {
+        let exported_link = exported
+            .links
+            .iter()
+            .find(|exported_link| exported_link.name == link.name)
+            .unwrap();
+        let what = format!("{path}: link {}", link.name);
+        assert_relatively_close(
+            link.inertial.mass.value,
+            exported_link.inertial.mass.value,
+            &format!("{what}: mass"),
+        );
+        assert_pose_close(
+            &link.inertial.origin,
+            &exported_link.inertial.origin,
+            &format!("{what}: center of mass"),
+        );
+        let tensor = |inertia: &urdf_rs::Inertia| {
+            [
+                inertia.ixx,
+                inertia.ixy,
+                inertia.ixz,
+                inertia.iyy,
+                inertia.iyz,
+                inertia.izz,
+            ]
+        };
+        let (expected, actual) = (
+            tensor(&link.inertial.inertia),
+            tensor(&exported_link.inertial.inertia),
+        );
+        for i in 0..6 {
+            assert_relatively_close(expected[i], actual[i], &format!("{what}: inertia[{i}]"));
+        }
+        assert_eq!(
+            link.visual.len(),
+            exported_link.visual.len(),
+            "{what}: visual count"
+        );
+        assert_eq!(
+            link.collision.len(),
+            exported_link.collision.len(),
+            "{what}: collision count"
+        );
+
+        for (i, (visual, exported_visual)) in
+            link.visual.iter().zip(&exported_link.visual).enumerate()
+        {
+            let what = format!("{what}: visual {i}");
+            assert_eq!(visual.name, exported_visual.name, "{what}: name");
+            assert_eq!(
+                visual.material.as_ref().map(|material| &material.name),
+                exported_visual
+                    .material
+                    .as_ref()
+                    .map(|material| &material.name),
+                "{what}: material name"
+            );
+
+            assert_pose_close(&visual.origin, &exported_visual.origin, &what);
+            assert_same_geometry(&visual.geometry, &exported_visual.geometry, &what);
+            if let Some(rgba) = resolved_rgba(original, visual.material.as_ref()) {
+                let exported_rgba = resolved_rgba(exported, exported_visual.material.as_ref())
+                    .unwrap_or_else(|| panic!("{what}: missing material color"));
+                for i in 0..4 {
+                    assert_close(rgba[i], exported_rgba[i], &format!("{what}: rgba[{i}]"));
+                }
+            }
+            assert_eq!(
+                resolved_texture(original, visual.material.as_ref()),
+                resolved_texture(exported, exported_visual.material.as_ref()),
+                "{what}: texture"
+            );
+        }
+
+        for (i, (collision, exported_collision)) in link
+            .collision
+            .iter()
+            .zip(&exported_link.collision)
+            .enumerate()
+        {
+            let what = format!("{what}: collision {i}");
+            assert_eq!(collision.name, exported_collision.name, "{what}: name");
+
+            assert_pose_close(&collision.origin, &exported_collision.origin, &what);
+            assert_same_geometry(&collision.geometry, &exported_collision.geometry, &what);
+        }
+    }
+
+    assert_eq!(
+        original.joints.len(),
+        exported.joints.len(),
+        "{path}: joint count"
+    );
+    for joint in &original.joints {
+        let what = format!("{path}: joint {}", joint.name);
+        let exported_joint = exported
+            .joints
+            .iter()
+            .find(|exported_joint| exported_joint.name == joint.name)
+            .unwrap_or_else(|| panic!("{what}: missing"));
+
+        assert_eq!(
+            joint.parent.link, exported_joint.parent.link,
+            "{what}: parent"
+        );
+        assert_eq!(joint.child.link, exported_joint.child.link, "{what}: child");
+        assert_eq!(joint.joint_type, exported_joint.joint_type, "{what}: type");
+        assert_pose_close(&joint.origin, &exported_joint.origin, &what);
+        if joint.joint_type != JointType::Fixed {
+            let axis = glam::DVec3::from_array(joint.axis.xyz.0).normalize();
+            let exported_axis = glam::DVec3::from_array(exported_joint.axis.xyz.0).normalize();
+            assert!(
+                axis.dot(exported_axis) >= 1.0 - TOLERANCE,
+                "{what}: expected axis {axis}, got {exported_axis}"
+            );
+        }
+        let dynamics = joint
+            .dynamics
+            .as_ref()
+            .map(|dynamics| [dynamics.damping, dynamics.friction])
+            .unwrap_or_default();
+        let exported_dynamics = exported_joint
+            .dynamics
+            .as_ref()
+            .map(|dynamics| [dynamics.damping, dynamics.friction])
+            .unwrap_or_default();
+        assert_close(
+            dynamics[0],
+            exported_dynamics[0],
+            &format!("{what}: damping"),
+        );
+        assert_close(
+            dynamics[1],
+            exported_dynamics[1],
+            &format!("{what}: friction"),
+        );
+        match (&joint.safety_controller, &exported_joint.safety_controller) {
+            (None, None) => {}
+            (Some(safety_controller), Some(exported_safety_controller)) => {
+                let values = |safety_controller: &urdf_rs::SafetyController| {
+                    [
+                        safety_controller.soft_lower_limit,
+                        safety_controller.soft_upper_limit,
+                        safety_controller.k_position,
+                        safety_controller.k_velocity,
+                    ]
+                };
+                let (expected, actual) = (
+                    values(safety_controller),
+                    values(exported_safety_controller),
+                );
+                for i in 0..4 {
+                    assert_close(
+                        expected[i],
+                        actual[i],
+                        &format!("{what}: safety controller[{i}]"),
+                    );
+                }
+            }
+            (expected, actual) => {
+                panic!("{what}: expected safety controller {expected:?}, got {actual:?}")
+            }
+        }
+        match (&joint.mimic, &exported_joint.mimic) {
+            (None, None) => {}
+            (Some(mimic), Some(exported_mimic)) => {
+                assert_eq!(mimic.joint, exported_mimic.joint, "{what}: mimic leader");
+                assert_close(
+                    mimic.multiplier.unwrap_or(1.0),
+                    exported_mimic.multiplier.unwrap_or(1.0),
+                    &format!("{what}: mimic multiplier"),
+                );
+                assert_close(
+                    mimic.offset.unwrap_or(0.0),
+                    exported_mimic.offset.unwrap_or(0.0),
+                    &format!("{what}: mimic offset"),
+                );
+            }
+            (expected, actual) => panic!("{what}: expected mimic {expected:?}, got {actual:?}"),
+        }
+        if matches!(joint.joint_type, JointType::Revolute | JointType::Prismatic) {
+            assert_close(
+                joint.limit.lower,
+                exported_joint.limit.lower,
+                &format!("{what}: lower limit"),
+            );
+            assert_close(
+                joint.limit.upper,
+                exported_joint.limit.upper,
+                &format!("{what}: upper limit"),
+            );
+        }
+        assert_close(
+            joint.limit.effort,
+            exported_joint.limit.effort,
+            &format!("{what}: effort limit"),
+        );
+        assert_close(
+            joint.limit.velocity,
+            exported_joint.limit.velocity,
+            &format!("{what}: velocity limit"),
+        );
+    }
+}
+
+#[test]
+fn cube_round_trips() {
+    assert_round_trips("model_pkg/urdf/cube.xml");
+}
+
+#[test]
+fn diff_bot_round_trips() {
+    assert_round_trips("model_pkg/urdf/diff_bot.xml");
+}
+
+#[test]
+fn issue_test_round_trips() {
+    assert_round_trips("urdf_tutorial/urdfs/issue_test.xml");
+}
+
+#[test]
+fn model_load_test_round_trips() {
+    assert_round_trips("urdf_tutorial/urdfs/model_load_test.xml");
+}
+
+#[test]
+fn tutorial_bot_round_trips() {
+    assert_round_trips("urdf_tutorial/urdfs/tutorial_bot.xml");
+}
+
+#[test]
+fn tutorial_bot_round_trips_in_every_coordinate_convention() {
+    for coordinates in [
+        UrdfCoordinates::ConvertToYUp,
+        UrdfCoordinates::RootRotation,
+        UrdfCoordinates::Untouched,
+    ] {
+        for link_hierarchy in [false, true] {
+            assert_round_trips_with(
+                "urdf_tutorial/urdfs/tutorial_bot.xml",
+                UrdfSpawnSettings {
+                    link_hierarchy,
+                    coordinates,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+#[test]
+fn full_urdf_tutorial_bot_round_trips() {
+    assert_round_trips("urdf_tutorial/urdfs/full_urdf_tutorial_bot.xml");
+}
+
+#[test]
+fn planar_floating_and_spherical_joints_round_trip() {
+    let urdf = load_urdf(
+        br#"<robot name="planar_and_floating">
+            <link name="base"/>
+            <link name="slider"/>
+            <link name="free"/>
+            <link name="ball"/>
+            <joint name="base_to_slider" type="planar">
+                <parent link="base"/>
+                <child link="slider"/>
+                <origin xyz="0 0 0.5" rpy="0 0 0"/>
+                <axis xyz="0 0 1"/>
+            </joint>
+            <joint name="free_to_ball" type="spherical">
+                <parent link="free"/>
+                <child link="ball"/>
+                <origin xyz="0 0 0.2" rpy="0 0 0"/>
+            </joint>
+            <joint name="slider_to_free" type="floating">
+                <parent link="slider"/>
+                <child link="free"/>
+                <origin xyz="1 0 0" rpy="0 0 0"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    assert_urdf_round_trips("planar_and_floating", &urdf, UrdfSpawnSettings::default());
+}
+
+#[test]
+fn diagonal_and_negative_joint_axes_round_trip() {
+    let urdf = load_urdf(
+        br#"<robot name="diagonal_axes">
+            <link name="base"/>
+            <link name="arm"/>
+            <link name="slider"/>
+            <joint name="base_to_arm" type="revolute">
+                <parent link="base"/>
+                <child link="arm"/>
+                <origin xyz="0 0 0.5" rpy="0.3 0 1.2"/>
+                <axis xyz="0 0.7071 0.7071"/>
+                <limit lower="-1.0" upper="0.5" effort="10" velocity="1"/>
+                <dynamics damping="0.7" friction="0.2"/>
+            </joint>
+            <joint name="arm_to_slider" type="prismatic">
+                <parent link="arm"/>
+                <child link="slider"/>
+                <origin xyz="0.2 0 0" rpy="0 0 0"/>
+                <axis xyz="-1 0 0"/>
+                <limit lower="0" upper="0.3" effort="10" velocity="1"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    for coordinates in [
+        UrdfCoordinates::ConvertToYUp,
+        UrdfCoordinates::RootRotation,
+        UrdfCoordinates::Untouched,
+    ] {
+        assert_urdf_round_trips(
+            "diagonal_axes",
+            &urdf,
+            UrdfSpawnSettings {
+                coordinates,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+#[test]
+fn mimic_joints_round_trip() {
+    let urdf = load_urdf(
+        br#"<robot name="gripper">
+            <link name="palm"/>
+            <link name="left_finger"/>
+            <link name="right_finger"/>
+            <joint name="palm_to_left_finger" type="revolute">
+                <parent link="palm"/>
+                <child link="left_finger"/>
+                <origin xyz="0 0.05 0" rpy="0 0 0"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="0" upper="0.5" effort="10" velocity="1"/>
+                <safety_controller soft_lower_limit="0.05" soft_upper_limit="0.45" k_position="15" k_velocity="10"/>
+            </joint>
+            <joint name="palm_to_right_finger" type="revolute">
+                <parent link="palm"/>
+                <child link="right_finger"/>
+                <origin xyz="0 -0.05 0" rpy="0 0 0"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="-0.5" upper="0" effort="10" velocity="1"/>
+                <mimic joint="palm_to_left_finger" multiplier="-1" offset="0.1"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    assert_urdf_round_trips("gripper", &urdf, UrdfSpawnSettings::default());
+}
+
+#[test]
+fn merged_fixed_joints_round_trip() {
+    for path in [
+        "urdf_tutorial/urdfs/tutorial_bot.xml",
+        "urdf_tutorial/urdfs/full_urdf_tutorial_bot.xml",
+    ] {
+        for link_hierarchy in [false, true] {
+            assert_round_trips_with(
+                path,
+                UrdfSpawnSettings {
+                    link_hierarchy,
+                    merge_fixed_joints: true,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+#[test]
+fn textured_materials_round_trip() {
+    let urdf = load_urdf(
+        br#"<robot name="textured">
+            <material name="checkered">
+                <color rgba="1 0.5 0.5 1"/>
+                <texture filename="package://model_pkg/textures/checker.png"/>
+            </material>
+            <link name="base">
+                <visual>
+                    <geometry><box size="1 1 1"/></geometry>
+                    <material name="checkered"/>
+                </visual>
+                <visual>
+                    <geometry><sphere radius="0.5"/></geometry>
+                    <material name="inline">
+                        <texture filename="package://model_pkg/textures/stripes.png"/>
+                    </material>
+                </visual>
+            </link>
+        </robot>"#,
+    )
+    .unwrap();
+    assert_urdf_round_trips("textured", &urdf, UrdfSpawnSettings::default());
+}
+
+#[test]
+fn mesh_scales_are_spawned_and_round_trip() {
+    let urdf = load_urdf(
+        br#"<robot name="scaled">
+            <link name="base">
+                <visual>
+                    <geometry><mesh filename="package://model_pkg/meshes/part.obj" scale="0.001 0.002 0.003"/></geometry>
+                </visual>
+                <collision>
+                    <geometry><mesh filename="package://model_pkg/meshes/part.obj" scale="0.001 0.002 0.003"/></geometry>
+                </collision>
+            </link>
+        </robot>"#,
+    )
+    .unwrap();
+
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    let scales = world
+        .query_filtered::<&Transform, Or<(With<LinkVisual>, With<LinkCollision>)>>()
+        .iter(&world)
+        .map(|transform| transform.scale)
+        .collect::<Vec<_>>();
+    assert_eq!(scales.len(), 2);
+    for scale in scales {
+        // geometry is spawned in its link's urdf axes, so the scale keeps urdf's order.
+        assert!(
+            scale.abs_diff_eq(glam::Vec3::new(0.001, 0.002, 0.003), 1e-9),
+            "expected urdf's scale, got {scale}"
+        );
+    }
+
+    assert_urdf_round_trips("scaled", &urdf, UrdfSpawnSettings::default());
+}
+
+#[test]
+fn urdfs_spawned_twice_are_read_back_out_separately() {
+    let urdf = load("model_pkg/urdf/diff_bot.xml");
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+    spawn_into(&mut world, &urdf, Transform::from_xyz(2.0, 0.0, 0.0));
+
+    let urdfs = read_back(&mut world);
+    let mut names = urdfs.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    let second_name = format!("{}_1", urdf.robot.name);
+    assert_eq!(names, [urdf.robot.name.clone(), second_name.clone()]);
+    assert_same_robot("diff_bot", &urdf, &urdfs[&urdf.robot.name]);
+    assert_same_robot("diff_bot_1", &urdf, &urdfs[&second_name]);
+}
+
+#[test]
+fn round_tripped_urdfs_survive_xml() {
+    for path in [
+        "model_pkg/urdf/diff_bot.xml",
+        "urdf_tutorial/urdfs/full_urdf_tutorial_bot.xml",
+    ] {
+        let original = load(path);
+        let xml = round_trip(&original, UrdfSpawnSettings::default())
+            .to_xml_string()
+            .unwrap_or_else(|err| panic!("{path}: {err}"));
+        let reparsed =
+            load_urdf(xml.as_bytes()).unwrap_or_else(|err| panic!("{path}: {err}\n{xml}"));
+        assert_same_robot(path, &original, &reparsed);
+    }
+}
+
+#[test]
+fn detached_floating_joints_are_exported_as_floating_joints() {
+    let urdf = load_urdf(
+        br#"<robot name="detached">
+            <link name="base"/>
+            <link name="arm"/>
+            <link name="free"/>
+            <link name="on_free"/>
+            <joint name="base_to_arm" type="continuous">
+                <parent link="base"/>
+                <child link="arm"/>
+                <axis xyz="0 0 1"/>
+            </joint>
+            <joint name="base_to_free" type="floating">
+                <parent link="base"/>
+                <child link="free"/>
+                <origin xyz="1 0 0" rpy="0 0 0"/>
+            </joint>
+            <joint name="free_to_on_free" type="fixed">
+                <parent link="free"/>
+                <child link="on_free"/>
+            </joint>
+        </robot>"#,
+    )
+    .unwrap();
+    let mut world = spawn(
+        &urdf,
+        UrdfSpawnSettings {
+            detach_floating_joints: true,
+            ..Default::default()
+        },
+    );
+    let free = entity_named(&mut world, "free");
+    assert!(
+        world
+            .get::<bevy_serialization_extras::prelude::link::JointFlag>(free)
+            .is_none(),
+        "free should be spawned without a joint"
+    );
+
+    let exported = read_back(&mut world).remove("detached").unwrap();
+    assert_same_robot("detached", &urdf, &exported);
+}
+
+#[test]
+fn save_requests_write_urdfs_that_load_back() {
+    use bevy::{
+        asset::{
+            io::{
+                file::{FileAssetReader, FileAssetWriter},
+                AssetSource,
+            },
+            AssetApp, AssetPlugin,
+        },
+        prelude::*,
+    };
+    use bevy_serialization_urdf::{
+        savers::urdf_saver::SaveUrdfRequest, systems::save_requested_urdfs,
+    };
+
+    let folder =
+        std::env::temp_dir().join(format!("bevy_serialization_urdf_{}", std::process::id()));
+    std::fs::create_dir_all(&folder).unwrap();
+    let mut app = App::new();
+    app.register_asset_source("saved", {
+        let folder = folder.clone();
+        AssetSource::build()
+            .with_reader({
+                let folder = folder.clone();
+                move || Box::new(FileAssetReader::new(folder.clone()))
+            })
+            .with_writer(move |create_root| {
+                Some(Box::new(FileAssetWriter::new(folder.clone(), create_root)))
+            })
+    })
+    .add_plugins((MinimalPlugins, AssetPlugin::default()))
+    .add_event::<SaveUrdfRequest>()
+    .add_systems(Update, save_requested_urdfs);
+
+    let original = load("model_pkg/urdf/diff_bot.xml");
+    spawn_into(app.world_mut(), &original, Transform::IDENTITY);
+    app.world_mut().send_event(SaveUrdfRequest {
+        structure: original.robot.name.clone(),
+        path: "saved://diff_bot.xml".into(),
+    });
+
+    // the file is written in the background, so give it a moment to show up in full.
+    let saved_path = folder.join("diff_bot.xml");
+    let mut saved = None;
+    for _ in 0..500 {
+        app.update();
+        if let Some(urdf) = std::fs::read(&saved_path)
+            .ok()
+            .and_then(|bytes| load_urdf(&bytes).ok())
+        {
+            saved = Some(urdf);
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    std::fs::remove_dir_all(&folder).unwrap();
+    let saved = saved.expect("the requested urdf should be saved");
+    assert_same_robot("diff_bot.xml", &original, &saved);
+}
@@ -0,0 +1,85 @@
+//! checks that `<texture>`s are spawned as [`TextureFile`]s, and applied to their entity's material.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_serialization_urdf::{
+    components::{LinkVisual, TextureFile},
+    loaders::urdf_loader::load_urdf,
+    resources::UrdfSpawnSettings,
+    systems::apply_texture_files,
+};
+use common::spawn;
+
+#[test]
+fn textures_are_spawned_on_their_visual() {
+    let urdf = load_urdf(
+        br#"<robot name="textured">
+            <link name="base">
+                <visual>
+                    <geometry><box size="1 1 1"/></geometry>
+                    <material name="checkered">
+                        <texture filename="package://model_pkg/textures/checker.png"/>
+                    </material>
+                </visual>
+            </link>
+        </robot>"#,
+    )
+    .unwrap();
+
+    let mut world = spawn(&urdf, UrdfSpawnSettings::default());
+
+    let textures = world
+        .query_filtered::<&TextureFile, With<LinkVisual>>()
+        .iter(&world)
+        .map(|texture| texture.source.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(textures, ["package://model_pkg/textures/checker.png"]);
+}
+
+#[test]
+fn texture_files_become_tinted_base_color_textures() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .init_asset::<StandardMaterial>()
+        .add_systems(Update, apply_texture_files);
+
+    let tint = Color::srgb(1.0, 0.5, 0.5);
+    let untextured = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: tint,
+            ..default()
+        });
+    let e = app
+        .world_mut()
+        .spawn((
+            TextureFile {
+                source: "textures/checker.png".to_owned(),
+            },
+            untextured.clone(),
+        ))
+        .id();
+    app.update();
+
+    let texture: Handle<Image> = app
+        .world()
+        .resource::<AssetServer>()
+        .load("textures/checker.png");
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    let textured = materials
+        .get(app.world().get::<Handle<StandardMaterial>>(e).unwrap())
+        .unwrap();
+    assert_eq!(textured.base_color_texture, Some(texture));
+    assert_eq!(
+        textured.base_color, tint,
+        "the material's color should be kept as a tint"
+    );
+    assert_eq!(
+        materials.get(&untextured).unwrap().base_color_texture,
+        None,
+        "the original material may be shared, so it should be left alone"
+    );
+}
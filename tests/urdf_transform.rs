@@ -0,0 +1,91 @@
+//! checks [`UrdfTransform`]'s conversions between urdf poses and bevy transforms.
+
+mod common;
+
+use std::f64::consts::FRAC_PI_2;
+
+use bevy_serialization_urdf::wrappers::UrdfTransform;
+use bevy_transform::prelude::Transform;
+use common::{urdf_rotation, TOLERANCE};
+use glam::Vec3;
+use urdf_rs::Pose;
+
+fn pose(xyz: [f64; 3], rpy: [f64; 3]) -> Pose {
+    Pose {
+        xyz: urdf_rs::Vec3(xyz),
+        rpy: urdf_rs::Vec3(rpy),
+    }
+}
+
+fn assert_vec3_close(expected: Vec3, actual: Vec3, what: &str) {
+    assert!(
+        expected.distance(actual) <= TOLERANCE as f32,
+        "{what}: expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn poses_survive_urdf_to_bevy_to_urdf() {
+    let angles = [-3.1, -FRAC_PI_2, -1.0, -0.25, 0.0, 0.5, 1.57075, 2.4, 3.1];
+    for roll in angles {
+        for pitch in angles {
+            for yaw in angles {
+                let original = pose([0.19, -0.22, 0.35], [roll, pitch, yaw]);
+                let transform = Transform::from(UrdfTransform::from(original.clone()));
+                let round_tripped = Pose::from(UrdfTransform::from(&transform));
+
+                for i in 0..3 {
+                    assert!(
+                        (original.xyz[i] - round_tripped.xyz[i]).abs() <= TOLERANCE,
+                        "xyz of {original:?} came back as {round_tripped:?}"
+                    );
+                }
+                // rpy isn't unique(e.g: at pitch = ±pi/2), so compare the rotations themselves.
+                let angle = urdf_rotation(&original).angle_between(urdf_rotation(&round_tripped));
+                assert!(
+                    angle <= TOLERANCE,
+                    "rotation of {original:?} came back as {round_tripped:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn urdf_up_is_bevy_up() {
+    let transform = Transform::from(UrdfTransform::from(pose([0.0, 0.0, 1.0], [0.0; 3])));
+    assert_vec3_close(Vec3::Y, transform.translation, "translation along urdf z");
+}
+
+#[test]
+fn urdf_basis_keeps_handedness() {
+    // urdf x cross urdf y = urdf z has to hold for the converted axes too, or rotations come out mirrored.
+    let x = Transform::from(UrdfTransform::from(pose([1.0, 0.0, 0.0], [0.0; 3]))).translation;
+    let y = Transform::from(UrdfTransform::from(pose([0.0, 1.0, 0.0], [0.0; 3]))).translation;
+    let z = Transform::from(UrdfTransform::from(pose([0.0, 0.0, 1.0], [0.0; 3]))).translation;
+    assert_vec3_close(z, x.cross(y), "x cross y");
+}
+
+#[test]
+fn urdf_yaw_turns_around_bevy_up() {
+    let transform = Transform::from(UrdfTransform::from(pose([0.0; 3], [0.0, 0.0, FRAC_PI_2])));
+    let urdf_x = Transform::from(UrdfTransform::from(pose([1.0, 0.0, 0.0], [0.0; 3]))).translation;
+    let urdf_y = Transform::from(UrdfTransform::from(pose([0.0, 1.0, 0.0], [0.0; 3]))).translation;
+    assert_vec3_close(
+        urdf_y,
+        transform.rotation * urdf_x,
+        "urdf x yawed a quarter turn",
+    );
+}
+
+#[test]
+fn urdf_pitch_points_x_down() {
+    // the legs in `issue_test.xml`: a box along x, pitched a quarter turn to hang along -z.
+    let transform = Transform::from(UrdfTransform::from(pose([0.0; 3], [0.0, 1.57075, 0.0])));
+    let urdf_x = Transform::from(UrdfTransform::from(pose([1.0, 0.0, 0.0], [0.0; 3]))).translation;
+    assert_vec3_close(
+        -Vec3::Y,
+        transform.rotation * urdf_x,
+        "urdf x pitched a quarter turn",
+    );
+}